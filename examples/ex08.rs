@@ -0,0 +1,33 @@
+use anyhow::Result;
+use rust_stats::dataset::stream_source;
+use rust_stats::descriptive::RunningStats;
+use rust_stats::source::DataSource;
+
+// Offline, streaming load: `cargo run --example ex08 -- ds_salaries.csv`,
+// or pipe the CSV in with no argument. Memory use does not depend on the
+// size of the input.
+fn main() {
+    let source = match std::env::args().nth(1) {
+        Some(path) => DataSource::path(path),
+        None => DataSource::Stdin,
+    };
+
+    let stats: Result<RunningStats> = stream_source(source).and_then(|records| {
+        records
+            .map(|record| record.map(|record| record.salary_in_usd))
+            .collect()
+    });
+
+    match stats {
+        Ok(stats) => {
+            println!("Streamed {} salaries", stats.count());
+            println!("Missing salaries: {}", stats.missing());
+            println!("Mean salary in usd: {:?}", stats.mean());
+            println!("Std of salary in usd: {:?}", stats.std());
+            println!("Range of salary in usd: {:?}", stats.range());
+        }
+        Err(error) => {
+            eprint!("Error loading dataset: {}", error)
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A closed set of codes used by one of the dataset's categorical fields.
+///
+/// Variants are declared in their natural order, so `Ord` sorts them from
+/// smallest/most junior to largest/most senior.
+pub trait Category: Copy + Ord + std::hash::Hash + 'static {
+    /// Every variant, in natural order.
+    const ALL: &'static [Self];
+
+    /// Code as written in `ds_salaries.csv`, e.g. `"SE"`.
+    fn code(self) -> &'static str;
+
+    /// Human-readable name, e.g. `"Senior"`.
+    fn label(self) -> &'static str;
+
+    /// Position of `self` in [`Category::ALL`].
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|&c| c == self).unwrap()
+    }
+
+    /// Looks a variant up by its code (case-insensitive).
+    fn from_code(code: &str) -> Result<Self> {
+        match Self::ALL
+            .iter()
+            .find(|c| c.code().eq_ignore_ascii_case(code))
+        {
+            Some(&c) => Ok(c),
+            None => {
+                let codes: Vec<&str> = Self::ALL.iter().map(|c| c.code()).collect();
+                bail!("unknown code '{}', expected one of {:?}", code, codes)
+            }
+        }
+    }
+}
+
+macro_rules! category {
+    (
+        $(#[$meta:meta])*
+        $name:ident { $($variant:ident = $code:literal, $label:literal;)+ }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+        pub enum $name {
+            $(
+                #[serde(rename = $code)]
+                $variant,
+            )+
+        }
+
+        impl Category for $name {
+            const ALL: &'static [Self] = &[$($name::$variant),+];
+
+            fn code(self) -> &'static str {
+                match self {
+                    $($name::$variant => $code,)+
+                }
+            }
+
+            fn label(self) -> &'static str {
+                match self {
+                    $($name::$variant => $label,)+
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.code())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = anyhow::Error;
+
+            fn from_str(code: &str) -> Result<Self> {
+                Self::from_code(code)
+            }
+        }
+    };
+}
+
+category! {
+    /// `experience_level`, from entry level to executive.
+    ExperienceLevel {
+        Entry = "EN", "Entry-level";
+        Mid = "MI", "Mid-level";
+        Senior = "SE", "Senior";
+        Executive = "EX", "Executive";
+    }
+}
+
+category! {
+    /// `employment_type`.
+    EmploymentType {
+        PartTime = "PT", "Part-time";
+        FullTime = "FT", "Full-time";
+        Contract = "CT", "Contract";
+        Freelance = "FL", "Freelance";
+    }
+}
+
+category! {
+    /// `company_size`, from small to large.
+    CompanySize {
+        Small = "S", "Small";
+        Medium = "M", "Medium";
+        Large = "L", "Large";
+    }
+}
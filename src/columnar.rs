@@ -0,0 +1,226 @@
+//! Apache Arrow IPC and Parquet import and export for [`Table`]s.
+//!
+//! Column types map one to one: `Int` is `Int64`, `Float` is `Float64`,
+//! `Str` is `Utf8`, `Bool` is `Boolean` and `Date` is `Date32`. On import,
+//! narrower integer and float types are widened, dictionary-encoded and
+//! large strings become `Str`, and `Date64` becomes `Date`. Requires the
+//! `columnar` feature.
+
+use crate::dataset::SalaryRecord;
+use crate::source::DataSource;
+use crate::table::{Column, DataType, Table};
+use anyhow::{bail, Context, Result};
+use arrow::array::{
+    Array, ArrayRef, BooleanArray, Date32Array, Float64Array, Int64Array, RecordBatch, StringArray,
+};
+use arrow::compute::{cast_with_options, concat, CastOptions};
+use arrow::datatypes::{DataType as ArrowType, Field, Schema, SchemaRef};
+use arrow::ipc::reader::{FileReader, StreamReader};
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatchReader;
+use bytes::Bytes;
+use chrono::{Datelike, NaiveDate};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::file::properties::WriterProperties;
+use std::fs::File;
+use std::io::{Cursor, Read, Write};
+use std::sync::Arc;
+
+/// Days from 0001-01-01 (chrono's day 1) to the Unix epoch.
+const EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+/// Magic bytes at the start of an Arrow IPC file (as opposed to a stream).
+const IPC_FILE_MAGIC: &[u8] = b"ARROW1";
+
+/// Reads the Parquet file behind `source` into a table.
+pub fn read_parquet(source: DataSource) -> Result<Table> {
+    match source {
+        DataSource::Path(path) => {
+            let file =
+                File::open(&path).with_context(|| format!("failed to open {}", path.display()))?;
+            read_parquet_from(file)
+        }
+        source => {
+            let mut bytes = Vec::new();
+            source.open()?.read_to_end(&mut bytes)?;
+            read_parquet_from(Bytes::from(bytes))
+        }
+    }
+}
+
+fn read_parquet_from<R: parquet::file::reader::ChunkReader + 'static>(input: R) -> Result<Table> {
+    let reader = ParquetRecordBatchReaderBuilder::try_new(input)
+        .context("invalid Parquet file")?
+        .build()?;
+    let schema = reader.schema();
+    let batches = reader.collect::<Result<Vec<_>, _>>()?;
+    table_from_batches(&schema, &batches)
+}
+
+/// Reads an Arrow IPC file or stream from `source` into a table.
+pub fn read_ipc(source: DataSource) -> Result<Table> {
+    let mut bytes = Vec::new();
+    source.open()?.read_to_end(&mut bytes)?;
+    if bytes.starts_with(IPC_FILE_MAGIC) {
+        let reader = FileReader::try_new(Cursor::new(bytes), None)?;
+        let schema = reader.schema();
+        let batches = reader.collect::<Result<Vec<_>, _>>()?;
+        table_from_batches(&schema, &batches)
+    } else {
+        let reader = StreamReader::try_new(Cursor::new(bytes), None)?;
+        let schema = reader.schema();
+        let batches = reader.collect::<Result<Vec<_>, _>>()?;
+        table_from_batches(&schema, &batches)
+    }
+}
+
+/// Salary records from a Parquet file; see [`Table::to_records`].
+pub fn load_parquet_records(source: DataSource) -> Result<Vec<SalaryRecord>> {
+    read_parquet(source)?.to_records()
+}
+
+/// Salary records from an Arrow IPC file or stream.
+pub fn load_ipc_records(source: DataSource) -> Result<Vec<SalaryRecord>> {
+    read_ipc(source)?.to_records()
+}
+
+/// Writes `table` as a zstd-compressed Parquet file.
+pub fn write_parquet<W: Write + Send>(writer: W, table: &Table) -> Result<()> {
+    let batch = to_record_batch(table)?;
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::ZSTD(ZstdLevel::default()))
+        .build();
+    let mut writer = ArrowWriter::try_new(writer, batch.schema(), Some(properties))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+/// Writes `table` in the Arrow IPC file format.
+pub fn write_ipc<W: Write>(writer: W, table: &Table) -> Result<()> {
+    let batch = to_record_batch(table)?;
+    let mut writer = FileWriter::try_new(writer, &batch.schema())?;
+    writer.write(&batch)?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// One record batch holding every row of `table`.
+pub fn to_record_batch(table: &Table) -> Result<RecordBatch> {
+    let mut fields = Vec::with_capacity(table.n_cols());
+    let mut arrays: Vec<ArrayRef> = Vec::with_capacity(table.n_cols());
+    for (name, column) in table.columns() {
+        let array: ArrayRef = match column {
+            Column::Float(v) => Arc::new(Float64Array::from(v.clone())),
+            Column::Int(v) => Arc::new(Int64Array::from(v.clone())),
+            Column::Str(v) => Arc::new(StringArray::from(v.clone())),
+            Column::Bool(v) => Arc::new(BooleanArray::from(v.clone())),
+            Column::Date(v) => Arc::new(Date32Array::from(
+                v.iter()
+                    .map(|d| d.map(|d| d.num_days_from_ce() - EPOCH_DAYS_FROM_CE))
+                    .collect::<Vec<_>>(),
+            )),
+        };
+        fields.push(Field::new(name, arrow_type(column.dtype()), true));
+        arrays.push(array);
+    }
+    let schema = Arc::new(Schema::new(fields));
+    Ok(RecordBatch::try_new(schema, arrays)?)
+}
+
+/// Concatenates `batches` into one table, column by column.
+pub fn table_from_batches(schema: &SchemaRef, batches: &[RecordBatch]) -> Result<Table> {
+    let mut table = Table::new();
+    for (i, field) in schema.fields().iter().enumerate() {
+        let target =
+            import_type(field.data_type()).with_context(|| format!("column '{}'", field.name()))?;
+        // Unsafe casts fail instead of turning out-of-range values into nulls.
+        let options = CastOptions {
+            safe: false,
+            ..Default::default()
+        };
+        let parts = batches
+            .iter()
+            .map(|batch| cast_with_options(batch.column(i), &arrow_type(target), &options))
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("column '{}'", field.name()))?;
+        let array = match parts.as_slice() {
+            [] => arrow::array::new_empty_array(&arrow_type(target)),
+            [only] => only.clone(),
+            _ => concat(&parts.iter().map(|a| a.as_ref()).collect::<Vec<_>>())?,
+        };
+        table.add_column(field.name().as_str(), column_from_array(target, &array))?;
+    }
+    Ok(table)
+}
+
+fn arrow_type(dtype: DataType) -> ArrowType {
+    match dtype {
+        DataType::Float => ArrowType::Float64,
+        DataType::Int => ArrowType::Int64,
+        DataType::Str => ArrowType::Utf8,
+        DataType::Bool => ArrowType::Boolean,
+        DataType::Date => ArrowType::Date32,
+    }
+}
+
+fn import_type(arrow: &ArrowType) -> Result<DataType> {
+    let dtype = match arrow {
+        ArrowType::Int8
+        | ArrowType::Int16
+        | ArrowType::Int32
+        | ArrowType::Int64
+        | ArrowType::UInt8
+        | ArrowType::UInt16
+        | ArrowType::UInt32
+        | ArrowType::UInt64 => DataType::Int,
+        ArrowType::Float16 | ArrowType::Float32 | ArrowType::Float64 => DataType::Float,
+        ArrowType::Utf8 | ArrowType::LargeUtf8 | ArrowType::Utf8View => DataType::Str,
+        ArrowType::Dictionary(_, values) => match import_type(values)? {
+            DataType::Str => DataType::Str,
+            _ => bail!("unsupported Arrow type {}", arrow),
+        },
+        ArrowType::Boolean => DataType::Bool,
+        ArrowType::Date32 | ArrowType::Date64 => DataType::Date,
+        other => bail!("unsupported Arrow type {}", other),
+    };
+    Ok(dtype)
+}
+
+/// `array` must already be cast to `arrow_type(dtype)`.
+fn column_from_array(dtype: DataType, array: &ArrayRef) -> Column {
+    let any = array.as_any();
+    match dtype {
+        DataType::Float => {
+            let values = any.downcast_ref::<Float64Array>().expect("cast to Float64");
+            Column::Float(values.iter().collect())
+        }
+        DataType::Int => {
+            let values = any.downcast_ref::<Int64Array>().expect("cast to Int64");
+            Column::Int(values.iter().collect())
+        }
+        DataType::Str => {
+            let values = any.downcast_ref::<StringArray>().expect("cast to Utf8");
+            Column::Str(values.iter().map(|v| v.map(str::to_string)).collect())
+        }
+        DataType::Bool => {
+            let values = any.downcast_ref::<BooleanArray>().expect("cast to Boolean");
+            Column::Bool(values.iter().collect())
+        }
+        DataType::Date => {
+            let values = any.downcast_ref::<Date32Array>().expect("cast to Date32");
+            Column::Date(
+                values
+                    .iter()
+                    .map(|days| {
+                        days.and_then(|d| {
+                            NaiveDate::from_num_days_from_ce_opt(d + EPOCH_DAYS_FROM_CE)
+                        })
+                    })
+                    .collect(),
+            )
+        }
+    }
+}
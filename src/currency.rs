@@ -0,0 +1,211 @@
+use crate::dataset::SalaryRecord;
+use crate::source::DataSource;
+use anyhow::{bail, Context, Result};
+use chrono::{Datelike, NaiveDate};
+use csv::ReaderBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+/// Currency every rate is quoted against.
+pub const BASE_CURRENCY: &str = "USD";
+
+#[derive(Deserialize)]
+struct RateRow {
+    date: String,
+    currency: String,
+    rate: f64,
+}
+
+/// Yearly exchange rates loaded from a `date,currency,rate` CSV.
+///
+/// `rate` is the number of units of `currency` one US dollar buys on
+/// `date` (so EUR is around 0.9). Every observation that falls in a year is
+/// averaged into that year's rate, which is how `salary_in_usd` was derived
+/// from `salary` in the first place. USD itself is always 1.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ExchangeRates {
+    yearly: HashMap<(String, i32), f64>,
+}
+
+impl ExchangeRates {
+    pub fn from_reader<R: Read>(rdr: R) -> Result<Self> {
+        let mut reader = ReaderBuilder::new().from_reader(rdr);
+        let mut sums: HashMap<(String, i32), (f64, u32)> = HashMap::new();
+        for result in reader.deserialize() {
+            let row: RateRow = result?;
+            let date = NaiveDate::parse_from_str(&row.date, "%Y-%m-%d")
+                .with_context(|| format!("invalid date '{}'", row.date))?;
+            if row.rate.is_nan() || row.rate <= 0.0 {
+                bail!("{} rate on {} must be positive", row.currency, row.date);
+            }
+            let entry = sums
+                .entry((row.currency.to_uppercase(), date.year()))
+                .or_default();
+            entry.0 += row.rate;
+            entry.1 += 1;
+        }
+        let yearly = sums
+            .into_iter()
+            .map(|(key, (sum, n))| (key, sum / n as f64))
+            .collect();
+        Ok(ExchangeRates { yearly })
+    }
+
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_source(DataSource::path(path.as_ref()))
+    }
+
+    pub fn from_source(source: DataSource) -> Result<Self> {
+        Self::from_reader(source.open()?)
+    }
+
+    /// Average units of `currency` per US dollar during `year`.
+    pub fn rate(&self, currency: &str, year: i32) -> Result<f64> {
+        let currency = currency.to_uppercase();
+        if currency == BASE_CURRENCY {
+            return Ok(1.0);
+        }
+        match self.yearly.get(&(currency.clone(), year)) {
+            Some(&rate) => Ok(rate),
+            None => bail!("no {} exchange rate for {}", currency, year),
+        }
+    }
+
+    /// Whether any year has a rate for `currency`; USD always does.
+    pub fn has_currency(&self, currency: &str) -> bool {
+        let currency = currency.to_uppercase();
+        currency == BASE_CURRENCY || self.yearly.keys().any(|(c, _)| *c == currency)
+    }
+
+    /// Converts `amount` from one currency to another at `year`'s rates.
+    pub fn convert(&self, amount: f64, from: &str, to: &str, year: i32) -> Result<f64> {
+        Ok(amount / self.rate(from, year)? * self.rate(to, year)?)
+    }
+}
+
+/// A record that could not be converted because the rate table has no
+/// rate for `currency` (its own or the target) in its `work_year`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MissingRate {
+    /// Position of the record in the dataset.
+    pub index: usize,
+    pub currency: String,
+    pub year: i32,
+}
+
+/// Salaries converted by [`convert_salaries`].
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct Conversion {
+    /// One entry per record; `None` without a salary, a currency or a rate.
+    pub salaries: Vec<Option<f32>>,
+    /// The records left unconverted for want of a rate.
+    pub missing_rates: Vec<MissingRate>,
+}
+
+/// `salary` of every record in the `target` currency, using the rates of
+/// the record's `work_year`.
+///
+/// Fails only when the table has no rates for `target` at all, which is
+/// almost always a typo. Records whose currency or year is not covered
+/// are left as `None` and listed in [`Conversion::missing_rates`].
+pub fn convert_salaries(
+    dataset: &[SalaryRecord],
+    rates: &ExchangeRates,
+    target: &str,
+) -> Result<Conversion> {
+    if !rates.has_currency(target) {
+        bail!("no {} exchange rates loaded", target.to_uppercase());
+    }
+    Ok(convert_all(dataset, rates, target))
+}
+
+fn convert_all(dataset: &[SalaryRecord], rates: &ExchangeRates, target: &str) -> Conversion {
+    let mut conversion = Conversion::default();
+    for (index, record) in dataset.iter().enumerate() {
+        let salary = convert_record(record, rates, target).unwrap_or_else(|missing| {
+            conversion
+                .missing_rates
+                .push(MissingRate { index, ..missing });
+            None
+        });
+        conversion.salaries.push(salary);
+    }
+    conversion
+}
+
+/// The converted salary, or the rate that is missing (with index 0).
+fn convert_record(
+    record: &SalaryRecord,
+    rates: &ExchangeRates,
+    target: &str,
+) -> std::result::Result<Option<f32>, MissingRate> {
+    let (Some(salary), Some(currency)) = (record.salary, &record.salary_currency) else {
+        return Ok(None);
+    };
+    let rate = |currency: &str| {
+        rates
+            .rate(currency, record.work_year)
+            .map_err(|_| MissingRate {
+                index: 0,
+                currency: currency.to_uppercase(),
+                year: record.work_year,
+            })
+    };
+    let converted = salary as f64 / rate(currency)? * rate(target)?;
+    Ok(Some(converted as f32))
+}
+
+/// A record whose own `salary_in_usd` disagrees with the converted salary.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsdMismatch {
+    /// Position of the record in the dataset.
+    pub index: usize,
+    /// `salary_in_usd` as provided.
+    pub provided: f32,
+    /// `salary` converted to USD with the loaded rates.
+    pub converted: f32,
+    /// `|provided - converted| / converted`.
+    pub relative_error: f32,
+}
+
+/// Outcome of [`check_salary_in_usd`].
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct UsdCheck {
+    pub mismatches: Vec<UsdMismatch>,
+    /// Records skipped for want of a rate, so partial rate tables still
+    /// check everything they cover.
+    pub missing_rates: Vec<MissingRate>,
+}
+
+/// Converts every salary to USD and reports the records whose provided
+/// `salary_in_usd` is off by more than `tolerance` (relative, so 0.05 is
+/// 5%). Records missing any of the three fields are not checked; records
+/// without a rate are listed in [`UsdCheck::missing_rates`].
+pub fn check_salary_in_usd(
+    dataset: &[SalaryRecord],
+    rates: &ExchangeRates,
+    tolerance: f32,
+) -> UsdCheck {
+    let conversion = convert_all(dataset, rates, BASE_CURRENCY);
+    let mismatches = dataset
+        .iter()
+        .zip(conversion.salaries)
+        .enumerate()
+        .filter_map(|(index, (record, converted))| {
+            let (provided, converted) = (record.salary_in_usd?, converted?);
+            let relative_error = (provided - converted).abs() / converted;
+            (relative_error > tolerance).then_some(UsdMismatch {
+                index,
+                provided,
+                converted,
+                relative_error,
+            })
+        })
+        .collect();
+    UsdCheck {
+        mismatches,
+        missing_rates: conversion.missing_rates,
+    }
+}
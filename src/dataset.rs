@@ -0,0 +1,264 @@
+use crate::categories::{Category, CompanySize, EmploymentType, ExperienceLevel};
+use crate::source::DataSource;
+use crate::table::{DataType, Value};
+use anyhow::{bail, Result};
+use csv::{DeserializeRecordsIntoIter, ErrorKind, ReaderBuilder};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::Read;
+
+/// Location of the salaries dataset used throughout the examples.
+pub const DS_SALARIES_URL: &str =
+    "https://raw.githubusercontent.com/kittenpub/database-repository/main/ds_salaries.csv";
+
+/// One row of `ds_salaries.csv`.
+///
+/// The fields that identify a position are required. Pay, location and
+/// company details may be left empty in the CSV and come through as `None`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SalaryRecord {
+    pub work_year: i32,
+    pub experience_level: ExperienceLevel,
+    pub employment_type: EmploymentType,
+    pub job_title: String,
+    pub salary: Option<f32>,
+    pub salary_currency: Option<String>,
+    pub salary_in_usd: Option<f32>,
+    pub employee_residence: Option<String>,
+    pub remote_ratio: Option<f32>,
+    pub company_location: Option<String>,
+    pub company_size: Option<CompanySize>,
+}
+
+impl SalaryRecord {
+    /// Field names and storage types, in CSV column order. Categories are
+    /// stored as their code strings, as in [`Table::from_records`].
+    ///
+    /// [`Table::from_records`]: crate::table::Table::from_records
+    pub const FIELDS: [(&'static str, DataType); 11] = [
+        ("work_year", DataType::Int),
+        ("experience_level", DataType::Str),
+        ("employment_type", DataType::Str),
+        ("job_title", DataType::Str),
+        ("salary", DataType::Float),
+        ("salary_currency", DataType::Str),
+        ("salary_in_usd", DataType::Float),
+        ("employee_residence", DataType::Str),
+        ("remote_ratio", DataType::Float),
+        ("company_location", DataType::Str),
+        ("company_size", DataType::Str),
+    ];
+
+    /// The field `name` as a [`Value`], `Null` when missing.
+    pub fn get(&self, name: &str) -> Result<Value> {
+        let value = match name {
+            "work_year" => self.work_year.into(),
+            "experience_level" => self.experience_level.code().into(),
+            "employment_type" => self.employment_type.code().into(),
+            "job_title" => self.job_title.as_str().into(),
+            "salary" => self.salary.into(),
+            "salary_currency" => self.salary_currency.clone().into(),
+            "salary_in_usd" => self.salary_in_usd.into(),
+            "employee_residence" => self.employee_residence.clone().into(),
+            "remote_ratio" => self.remote_ratio.into(),
+            "company_location" => self.company_location.clone().into(),
+            "company_size" => self.company_size.map(|size| size.code()).into(),
+            _ => bail!("unknown column '{}'", name),
+        };
+        Ok(value)
+    }
+}
+
+/// Downloads `url` and returns the body as text.
+#[cfg(feature = "url")]
+pub fn fetch_dataset(url: &str) -> Result<String> {
+    fetch_dataset_with(url, &crate::fetch::FetchOptions::default())
+}
+
+/// [`fetch_dataset`] with caching, checksum pinning or offline mode.
+#[cfg(feature = "url")]
+pub fn fetch_dataset_with(url: &str, options: &crate::fetch::FetchOptions) -> Result<String> {
+    let body = crate::fetch::fetch_with(url, options)?;
+    Ok(String::from_utf8(body)?)
+}
+
+/// Parses CSV text (with a header row) into salary records.
+pub fn load_dataset(csv_data: &str) -> Result<Vec<SalaryRecord>> {
+    load_from_reader(csv_data.as_bytes())
+}
+
+/// Reads and parses the CSV behind `source`.
+pub fn load_source(source: DataSource) -> Result<Vec<SalaryRecord>> {
+    load_from_reader(source.open()?)
+}
+
+/// Parses CSV (with a header row) from any reader into salary records.
+pub fn load_from_reader<R: Read>(rdr: R) -> Result<Vec<SalaryRecord>> {
+    stream_records(rdr).collect()
+}
+
+/// How the loaders react to a row that does not parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoadMode {
+    /// Stop at the first bad row and return its error.
+    #[default]
+    Strict,
+    /// Drop bad rows, only counting them.
+    Skip,
+    /// Drop bad rows and keep a [`RowError`] for each one.
+    Collect,
+}
+
+/// A row that could not be turned into a [`SalaryRecord`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RowError {
+    /// 1-based line in the input; the header is line 1.
+    pub line: u64,
+    /// Offending column, when the parser can tell.
+    pub column: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.column {
+            Some(column) => write!(
+                f,
+                "line {}, column '{}': {}",
+                self.line, column, self.message
+            ),
+            None => write!(f, "line {}: {}", self.line, self.message),
+        }
+    }
+}
+
+impl std::error::Error for RowError {}
+
+/// What a lenient load left out.
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct LoadReport {
+    /// Number of rows dropped.
+    pub skipped: usize,
+    /// One entry per dropped row; only filled in [`LoadMode::Collect`].
+    pub rejected: Vec<RowError>,
+}
+
+/// Records plus the report of rows that were left out.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Loaded {
+    pub records: Vec<SalaryRecord>,
+    pub report: LoadReport,
+}
+
+/// [`load_dataset`] with a choice of how to handle bad rows.
+pub fn load_dataset_with(csv_data: &str, mode: LoadMode) -> Result<Loaded> {
+    load_from_reader_with(csv_data.as_bytes(), mode)
+}
+
+/// [`load_source`] with a choice of how to handle bad rows.
+pub fn load_source_with(source: DataSource, mode: LoadMode) -> Result<Loaded> {
+    load_from_reader_with(source.open()?, mode)
+}
+
+/// [`load_from_reader`] with a choice of how to handle bad rows.
+///
+/// Only row-level problems are subject to `mode`; I/O failures always
+/// abort the load.
+pub fn load_from_reader_with<R: Read>(rdr: R, mode: LoadMode) -> Result<Loaded> {
+    collect_with(stream_records(rdr), mode)
+}
+
+/// Collects a record stream, applying `mode` to [`RowError`]s. Shared by the
+/// CSV and JSON Lines loaders.
+pub(crate) fn collect_with(
+    stream: impl Iterator<Item = Result<SalaryRecord>>,
+    mode: LoadMode,
+) -> Result<Loaded> {
+    let mut records = Vec::new();
+    let mut report = LoadReport::default();
+    for result in stream {
+        let error = match result {
+            Ok(record) => {
+                records.push(record);
+                continue;
+            }
+            Err(error) => error,
+        };
+        let row = error.downcast::<RowError>()?;
+        match mode {
+            LoadMode::Strict => return Err(row.into()),
+            LoadMode::Skip => report.skipped += 1,
+            LoadMode::Collect => {
+                report.skipped += 1;
+                report.rejected.push(row);
+            }
+        }
+    }
+    Ok(Loaded { records, report })
+}
+
+/// Opens `source` and returns a lazy iterator over its records.
+pub fn stream_source(source: DataSource) -> Result<Records<Box<dyn Read>>> {
+    Ok(stream_records(source.open()?))
+}
+
+/// Lazily parses CSV (with a header row) from `rdr`, one record at a time.
+///
+/// Nothing is buffered beyond the CSV reader's internal buffer, so this is
+/// the way to go for files that do not fit in memory. Rows that fail to
+/// parse come out as [`RowError`]s and the iterator carries on with the
+/// next row.
+pub fn stream_records<R: Read>(rdr: R) -> Records<R> {
+    let reader = ReaderBuilder::new().from_reader(rdr);
+    Records {
+        inner: reader.into_deserialize(),
+    }
+}
+
+/// Iterator returned by [`stream_records`] and [`stream_source`].
+pub struct Records<R> {
+    inner: DeserializeRecordsIntoIter<R, SalaryRecord>,
+}
+
+impl<R: Read> Iterator for Records<R> {
+    type Item = Result<SalaryRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let error = match self.inner.next()? {
+            Ok(record) => return Some(Ok(record)),
+            Err(error) => error,
+        };
+        Some(Err(self.row_error(error)))
+    }
+}
+
+impl<R: Read> Records<R> {
+    fn row_error(&mut self, error: csv::Error) -> anyhow::Error {
+        let (line, column, message) = match error.kind() {
+            ErrorKind::Deserialize { pos, err } => {
+                let column = err.field().and_then(|i| {
+                    let headers = self.inner.reader_mut().headers().ok()?;
+                    headers.get(i as usize).map(str::to_string)
+                });
+                (pos.as_ref(), column, err.kind().to_string())
+            }
+            ErrorKind::UnequalLengths {
+                pos,
+                expected_len,
+                len,
+            } => (
+                pos.as_ref(),
+                None,
+                format!("expected {} fields, found {}", expected_len, len),
+            ),
+            ErrorKind::Utf8 { pos, err } => (pos.as_ref(), None, err.to_string()),
+            _ => return error.into(),
+        };
+        RowError {
+            line: line.map_or(0, |pos| pos.line()),
+            column,
+            message,
+        }
+        .into()
+    }
+}
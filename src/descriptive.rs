@@ -0,0 +1,530 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::ops::{Add, Sub};
+
+/// A number the descriptive statistics accept: `f32`, `f64`, the integer
+/// types up to 64 bits, and references to any of them, so both slices and
+/// iterators of values work.
+///
+/// NaN in gives NaN out: a single NaN makes the mean, median, standard
+/// deviation and range NaN. Use the `_with` variants to skip NaN instead.
+pub trait Numeric: Copy {
+    /// What statistics come out as: the type itself for floats, `f64` for
+    /// integers.
+    type Output: Copy;
+    /// Type sums are accumulated in: a compensated [`NeumaierSum`] for
+    /// floats and `i128` for integers, which cannot overflow for any
+    /// realistic input length.
+    type Sum: Copy + Default + Add<Output = Self::Sum> + Sub<Output = Self::Sum>;
+
+    fn to_sum(self) -> Self::Sum;
+    fn sum_to_f64(sum: Self::Sum) -> f64;
+    fn to_f64(self) -> f64;
+    fn output(value: f64) -> Self::Output;
+    /// Total order used for the median and range; floats use `total_cmp`.
+    fn total_cmp(&self, other: &Self) -> Ordering;
+    /// Always false for integers.
+    fn is_nan(self) -> bool;
+}
+
+macro_rules! float_numeric {
+    ($($t:ty),*) => {$(
+        impl Numeric for $t {
+            type Output = $t;
+            type Sum = NeumaierSum;
+
+            fn to_sum(self) -> NeumaierSum {
+                NeumaierSum::from(self as f64)
+            }
+            fn sum_to_f64(sum: NeumaierSum) -> f64 {
+                sum.value()
+            }
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+            fn output(value: f64) -> $t {
+                value as $t
+            }
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                <$t>::total_cmp(self, other)
+            }
+            fn is_nan(self) -> bool {
+                <$t>::is_nan(self)
+            }
+        }
+    )*};
+}
+
+macro_rules! int_numeric {
+    ($($t:ty),*) => {$(
+        impl Numeric for $t {
+            type Output = f64;
+            type Sum = i128;
+
+            fn to_sum(self) -> i128 {
+                self as i128
+            }
+            fn sum_to_f64(sum: i128) -> f64 {
+                sum as f64
+            }
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+            fn output(value: f64) -> f64 {
+                value
+            }
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                self.cmp(other)
+            }
+            fn is_nan(self) -> bool {
+                false
+            }
+        }
+    )*};
+}
+
+float_numeric!(f32, f64);
+int_numeric!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl<T: Numeric> Numeric for &T {
+    type Output = T::Output;
+    type Sum = T::Sum;
+
+    fn to_sum(self) -> T::Sum {
+        (*self).to_sum()
+    }
+    fn sum_to_f64(sum: T::Sum) -> f64 {
+        T::sum_to_f64(sum)
+    }
+    fn to_f64(self) -> f64 {
+        (*self).to_f64()
+    }
+    fn output(value: f64) -> T::Output {
+        T::output(value)
+    }
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        T::total_cmp(*self, *other)
+    }
+    fn is_nan(self) -> bool {
+        (*self).is_nan()
+    }
+}
+
+/// Arithmetic mean of `data`. Floats are summed in `f64` with
+/// [`NeumaierSum`], integers exactly in `i128`.
+pub fn calc_mean<I>(data: I) -> Result<<I::Item as Numeric>::Output>
+where
+    I: IntoIterator,
+    I::Item: Numeric,
+{
+    let (sum, len) = sum_and_len(data);
+    if len == 0 {
+        bail!("cannot compute the mean of an empty column");
+    }
+    Ok(I::Item::output(I::Item::sum_to_f64(sum) / len as f64))
+}
+
+/// Median of `data`, the mean of the two middle values for even lengths.
+pub fn calc_median<I>(data: I) -> Result<<I::Item as Numeric>::Output>
+where
+    I: IntoIterator,
+    I::Item: Numeric,
+{
+    let mut data: Vec<I::Item> = data.into_iter().collect();
+    if data.is_empty() {
+        bail!("cannot compute the median of an empty column");
+    }
+    if data.iter().any(|value| value.is_nan()) {
+        return Ok(I::Item::output(f64::NAN));
+    }
+    data.sort_unstable_by(Numeric::total_cmp);
+    let len = data.len();
+    let median = if len.is_multiple_of(2) {
+        let mid1 = data[(len / 2) - 1].to_sum();
+        let mid2 = data[len / 2].to_sum();
+        I::Item::sum_to_f64(mid1 + mid2) / 2.0
+    } else {
+        data[len / 2].to_f64()
+    };
+    Ok(I::Item::output(median))
+}
+
+/// Sample standard deviation (n - 1 denominator) of `data`, computed in
+/// one pass with Welford's method in `f64`.
+pub fn calc_std<I>(data: I) -> Result<<I::Item as Numeric>::Output>
+where
+    I: IntoIterator,
+    I::Item: Numeric,
+{
+    let mut welford = Welford::default();
+    for value in data {
+        welford.push(value.to_f64());
+    }
+    if welford.count < 2 {
+        bail!("sample standard deviation needs at least two values");
+    }
+    Ok(I::Item::output(welford.sample_variance().sqrt()))
+}
+
+/// Difference between the largest and smallest value of `data`.
+pub fn range<I>(data: I) -> Result<<I::Item as Numeric>::Output>
+where
+    I: IntoIterator,
+    I::Item: Numeric,
+{
+    let mut data = data.into_iter();
+    let Some(first) = data.next() else {
+        bail!("cannot compute the range of an empty column");
+    };
+    let mut nan = first.is_nan();
+    let (min, max) = data.fold((first, first), |(min, max), value| {
+        nan |= value.is_nan();
+        (
+            std::cmp::min_by(min, value, Numeric::total_cmp),
+            std::cmp::max_by(max, value, Numeric::total_cmp),
+        )
+    });
+    if nan {
+        return Ok(I::Item::output(f64::NAN));
+    }
+    Ok(I::Item::output(I::Item::sum_to_f64(
+        max.to_sum() - min.to_sum(),
+    )))
+}
+
+fn sum_and_len<T: Numeric>(data: impl IntoIterator<Item = T>) -> (T::Sum, usize) {
+    data.into_iter()
+        .fold((T::Sum::default(), 0), |(sum, len), value| {
+            (sum + value.to_sum(), len + 1)
+        })
+}
+
+/// Floating-point sum with Neumaier's compensation: the rounding error of
+/// every addition is carried separately and added back at the end, so
+/// the result stays accurate however many values go in and however much
+/// their magnitudes differ.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct NeumaierSum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl NeumaierSum {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, value: f64) {
+        let sum = self.sum + value;
+        self.compensation += if self.sum.abs() >= value.abs() {
+            (self.sum - sum) + value
+        } else {
+            (value - sum) + self.sum
+        };
+        self.sum = sum;
+    }
+
+    pub fn value(&self) -> f64 {
+        // Past an infinity the compensation is `inf - inf`, i.e. NaN.
+        if self.sum.is_finite() {
+            self.sum + self.compensation
+        } else {
+            self.sum
+        }
+    }
+}
+
+impl From<f64> for NeumaierSum {
+    fn from(value: f64) -> Self {
+        NeumaierSum {
+            sum: value,
+            compensation: 0.0,
+        }
+    }
+}
+
+impl Add for NeumaierSum {
+    type Output = NeumaierSum;
+
+    fn add(mut self, other: NeumaierSum) -> NeumaierSum {
+        self.push(other.sum);
+        self.compensation += other.compensation;
+        self
+    }
+}
+
+impl Sub for NeumaierSum {
+    type Output = NeumaierSum;
+
+    fn sub(self, other: NeumaierSum) -> NeumaierSum {
+        self + NeumaierSum {
+            sum: -other.sum,
+            compensation: -other.compensation,
+        }
+    }
+}
+
+impl std::iter::Sum<f64> for NeumaierSum {
+    fn sum<I: Iterator<Item = f64>>(iter: I) -> Self {
+        let mut sum = NeumaierSum::new();
+        for value in iter {
+            sum.push(value);
+        }
+        sum
+    }
+}
+
+/// Welford's running mean and sum of squared deviations.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub(crate) struct Welford {
+    count: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Welford {
+    pub(crate) fn push(&mut self, x: f64) {
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    /// Needs at least two values.
+    pub(crate) fn sample_variance(&self) -> f64 {
+        self.m2 / (self.count - 1) as f64
+    }
+}
+
+/// Quantile of sorted values with linear interpolation between the two
+/// nearest ranks, the pandas and NumPy default.
+pub(crate) fn quantile_sorted(sorted: &[f64], q: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let position = q * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    let fraction = position - lower as f64;
+    Some(sorted[lower] + (sorted[upper] - sorted[lower]) * fraction)
+}
+
+/// What a statistic does with missing values (`None` or NaN).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingPolicy {
+    /// Leave them out and compute over the rest.
+    Skip,
+    /// Any missing value makes the result NaN.
+    Propagate,
+    /// Any missing value is an error.
+    Error,
+}
+
+/// A statistic together with how many missing values it left out. The
+/// value has the type [`Numeric::Output`] of the input: `f32` for `f32`
+/// columns, `f64` for `f64` and integer ones.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Stat<T = f32> {
+    pub value: T,
+    /// Missing values excluded under [`MissingPolicy::Skip`]; 0 otherwise.
+    pub excluded: usize,
+}
+
+/// Whether `value` counts as missing for the `_with` statistics.
+pub fn is_missing<T: Numeric>(value: Option<T>) -> bool {
+    value.is_none_or(Numeric::is_nan)
+}
+
+fn with_policy<T: Numeric>(
+    missing: usize,
+    policy: MissingPolicy,
+    compute: impl FnOnce() -> Result<T::Output>,
+) -> Result<Stat<T::Output>> {
+    if missing == 0 {
+        return Ok(Stat {
+            value: compute()?,
+            excluded: 0,
+        });
+    }
+    match policy {
+        MissingPolicy::Skip => Ok(Stat {
+            value: compute()?,
+            excluded: missing,
+        }),
+        MissingPolicy::Propagate => Ok(Stat {
+            value: T::output(f64::NAN),
+            excluded: 0,
+        }),
+        MissingPolicy::Error => bail!("column has {} missing values", missing),
+    }
+}
+
+fn present<T: Numeric>(data: &[Option<T>]) -> (Vec<T>, usize) {
+    let values: Vec<T> = data
+        .iter()
+        .copied()
+        .filter(|v| !is_missing(*v))
+        .flatten()
+        .collect();
+    let missing = data.len() - values.len();
+    (values, missing)
+}
+
+/// [`calc_mean`] over a column with missing values.
+pub fn calc_mean_with<T: Numeric>(
+    data: &[Option<T>],
+    policy: MissingPolicy,
+) -> Result<Stat<T::Output>> {
+    let (values, missing) = present(data);
+    with_policy::<T>(missing, policy, || calc_mean(values))
+}
+
+/// [`calc_median`] over a column with missing values.
+pub fn calc_median_with<T: Numeric>(
+    data: &[Option<T>],
+    policy: MissingPolicy,
+) -> Result<Stat<T::Output>> {
+    let (values, missing) = present(data);
+    with_policy::<T>(missing, policy, || calc_median(values))
+}
+
+/// [`calc_std`] over a column with missing values.
+pub fn calc_std_with<T: Numeric>(
+    data: &[Option<T>],
+    policy: MissingPolicy,
+) -> Result<Stat<T::Output>> {
+    let (values, missing) = present(data);
+    with_policy::<T>(missing, policy, || calc_std(values))
+}
+
+/// [`range`] over a column with missing values.
+pub fn range_with<T: Numeric>(
+    data: &[Option<T>],
+    policy: MissingPolicy,
+) -> Result<Stat<T::Output>> {
+    let (values, missing) = present(data);
+    with_policy::<T>(missing, policy, || range(values))
+}
+
+/// Single-pass accumulator for count, mean, standard deviation and range.
+///
+/// Memory use is constant, so it can sit at the end of a record stream
+/// where collecting the column first is not an option. Mean and variance
+/// are updated with Welford's method. Missing values are counted, not
+/// folded in; the `_with` methods apply a [`MissingPolicy`] to them.
+///
+/// The state serializes, so a partial pass can be saved as JSON and
+/// resumed later.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct RunningStats {
+    welford: Welford,
+    missing: u64,
+    /// `None` until the first value; JSON has no infinities to start from.
+    min: Option<f32>,
+    max: Option<f32>,
+}
+
+impl RunningStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Folds one more value into the running statistics. NaN is counted
+    /// as missing.
+    pub fn push(&mut self, value: f32) {
+        if value.is_nan() {
+            self.missing += 1;
+            return;
+        }
+        self.welford.push(value as f64);
+        self.min = Some(self.min.map_or(value, |min| min.min(value)));
+        self.max = Some(self.max.map_or(value, |max| max.max(value)));
+    }
+
+    /// Folds in a value that may be missing.
+    pub fn push_option(&mut self, value: Option<f32>) {
+        match value {
+            Some(value) => self.push(value),
+            None => self.missing += 1,
+        }
+    }
+
+    /// Number of non-missing values pushed.
+    pub fn count(&self) -> u64 {
+        self.welford.count
+    }
+
+    /// Number of missing values pushed.
+    pub fn missing(&self) -> u64 {
+        self.missing
+    }
+
+    /// Same as [`calc_mean`] over the non-missing values pushed so far.
+    pub fn mean(&self) -> Result<f32> {
+        if self.count() == 0 {
+            bail!("cannot compute the mean of an empty column");
+        }
+        Ok(self.welford.mean as f32)
+    }
+
+    /// Same as [`calc_std`] over the non-missing values pushed so far.
+    pub fn std(&self) -> Result<f32> {
+        if self.count() < 2 {
+            bail!("sample standard deviation needs at least two values");
+        }
+        Ok(self.welford.sample_variance().sqrt() as f32)
+    }
+
+    /// Same as [`range`] over the non-missing values pushed so far.
+    pub fn range(&self) -> Result<f32> {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => Ok(max - min),
+            _ => bail!("cannot compute the range of an empty column"),
+        }
+    }
+
+    pub fn mean_with(&self, policy: MissingPolicy) -> Result<Stat> {
+        with_policy::<f32>(self.missing as usize, policy, || self.mean())
+    }
+
+    pub fn std_with(&self, policy: MissingPolicy) -> Result<Stat> {
+        with_policy::<f32>(self.missing as usize, policy, || self.std())
+    }
+
+    pub fn range_with(&self, policy: MissingPolicy) -> Result<Stat> {
+        with_policy::<f32>(self.missing as usize, policy, || self.range())
+    }
+}
+
+impl Extend<f32> for RunningStats {
+    fn extend<I: IntoIterator<Item = f32>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl FromIterator<f32> for RunningStats {
+    fn from_iter<I: IntoIterator<Item = f32>>(iter: I) -> Self {
+        let mut stats = RunningStats::new();
+        stats.extend(iter);
+        stats
+    }
+}
+
+impl Extend<Option<f32>> for RunningStats {
+    fn extend<I: IntoIterator<Item = Option<f32>>>(&mut self, iter: I) {
+        for value in iter {
+            self.push_option(value);
+        }
+    }
+}
+
+impl FromIterator<Option<f32>> for RunningStats {
+    fn from_iter<I: IntoIterator<Item = Option<f32>>>(iter: I) -> Self {
+        let mut stats = RunningStats::new();
+        stats.extend(iter);
+        stats
+    }
+}
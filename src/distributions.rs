@@ -0,0 +1,46 @@
+use anyhow::Result;
+use statrs::distribution::{
+    Bernoulli, Binomial, Continuous, ContinuousCDF, Discrete, DiscreteCDF, Normal, Uniform,
+};
+
+/// P(X = 1) for a Bernoulli(p).
+pub fn calc_bern_prob(p: f64) -> Result<f64> {
+    let bern = Bernoulli::new(p)?;
+    let prob = bern.pmf(1);
+    Ok(prob)
+}
+
+/// P(X = x) for a Binomial(p, n).
+pub fn calc_binom_prob(p: f64, n: u64, x: u64) -> Result<f64> {
+    let binom = Binomial::new(p, n)?;
+    let prob = binom.pmf(x);
+    Ok(prob)
+}
+
+/// P(X <= x) for a Binomial(p, n).
+pub fn calc_binom_prob_less(p: f64, n: u64, x: u64) -> Result<f64> {
+    let binom = Binomial::new(p, n)?;
+    let prob = binom.cdf(x);
+    Ok(prob)
+}
+
+/// P(X <= x) for a Uniform(min, max).
+pub fn calc_unif_prob(min: f64, max: f64, x: f64) -> Result<f64> {
+    let unif = Uniform::new(min, max)?;
+    let prob = unif.cdf(x);
+    Ok(prob)
+}
+
+/// Density at `x` of a Normal(mean, std).
+pub fn calc_normal_density(mean: f64, std: f64, x: f64) -> Result<f64> {
+    let normal = Normal::new(mean, std)?;
+    let prob = normal.pdf(x);
+    Ok(prob)
+}
+
+/// P(X <= x) for a Normal(mean, std).
+pub fn calc_normal_prob(mean: f64, std: f64, x: f64) -> Result<f64> {
+    let normal = Normal::new(mean, std)?;
+    let prob = normal.cdf(x);
+    Ok(prob)
+}
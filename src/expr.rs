@@ -0,0 +1,628 @@
+//! A small filter expression language over named columns.
+//!
+//! Expressions are parsed from text such as
+//! `experience_level in ("SE", "EX") and salary_in_usd > 100000` or built
+//! with [`col`]: `col("company_location").eq("US")`. They evaluate against
+//! [`Table`] rows or [`SalaryRecord`]s, after a type check against the
+//! column types.
+//!
+//! Grammar, keywords case-insensitive:
+//!
+//! ```text
+//! expr       := and ("or" and)*
+//! and        := unary ("and" unary)*
+//! unary      := "not" unary | "(" expr ")" | column predicate
+//! predicate  := op literal | ["not"] "in" "(" literal ("," literal)* ")"
+//!             | "is" ["not"] "null"
+//! op         := "=" | "==" | "!=" | "<>" | "<" | "<=" | ">" | ">="
+//! literal    := "text" | 'text' | number | true | false
+//! ```
+//!
+//! Inside quotes, a backslash escapes the next character, e.g. `"a \"b\""`.
+//!
+//! Comparisons against a missing cell or NaN are false; select those rows
+//! with `is null`, which matches both. Number literals must be finite.
+//! Dates are written as text, e.g. `day >= "2023-01-01"`.
+
+use crate::dataset::SalaryRecord;
+use crate::table::{DataType, Table, Value};
+use anyhow::{bail, Result};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CmpOp {
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            CmpOp::Eq => ordering.is_eq(),
+            CmpOp::Ne => ordering.is_ne(),
+            CmpOp::Lt => ordering.is_lt(),
+            CmpOp::Le => ordering.is_le(),
+            CmpOp::Gt => ordering.is_gt(),
+            CmpOp::Ge => ordering.is_ge(),
+        }
+    }
+}
+
+impl fmt::Display for CmpOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            CmpOp::Eq => "=",
+            CmpOp::Ne => "!=",
+            CmpOp::Lt => "<",
+            CmpOp::Le => "<=",
+            CmpOp::Gt => ">",
+            CmpOp::Ge => ">=",
+        };
+        f.write_str(symbol)
+    }
+}
+
+/// A boolean expression over the columns of one row.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Compare {
+        column: String,
+        op: CmpOp,
+        value: Value,
+    },
+    In {
+        column: String,
+        values: Vec<Value>,
+    },
+    /// `column not in (...)`: unlike `Not(In)`, false for a missing cell.
+    NotIn {
+        column: String,
+        values: Vec<Value>,
+    },
+    IsNull {
+        column: String,
+    },
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+/// Starts a builder expression on the column `name`.
+pub fn col(name: impl Into<String>) -> Col {
+    Col(name.into())
+}
+
+/// A column reference; see [`col`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Col(String);
+
+impl Col {
+    fn compare(self, op: CmpOp, value: impl Into<Value>) -> Expr {
+        Expr::Compare {
+            column: self.0,
+            op,
+            value: value.into(),
+        }
+    }
+
+    pub fn eq(self, value: impl Into<Value>) -> Expr {
+        self.compare(CmpOp::Eq, value)
+    }
+
+    pub fn ne(self, value: impl Into<Value>) -> Expr {
+        self.compare(CmpOp::Ne, value)
+    }
+
+    pub fn lt(self, value: impl Into<Value>) -> Expr {
+        self.compare(CmpOp::Lt, value)
+    }
+
+    pub fn le(self, value: impl Into<Value>) -> Expr {
+        self.compare(CmpOp::Le, value)
+    }
+
+    pub fn gt(self, value: impl Into<Value>) -> Expr {
+        self.compare(CmpOp::Gt, value)
+    }
+
+    pub fn ge(self, value: impl Into<Value>) -> Expr {
+        self.compare(CmpOp::Ge, value)
+    }
+
+    pub fn is_in<V: Into<Value>>(self, values: impl IntoIterator<Item = V>) -> Expr {
+        Expr::In {
+            column: self.0,
+            values: values.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn not_in<V: Into<Value>>(self, values: impl IntoIterator<Item = V>) -> Expr {
+        Expr::NotIn {
+            column: self.0,
+            values: values.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn is_null(self) -> Expr {
+        Expr::IsNull { column: self.0 }
+    }
+
+    pub fn is_not_null(self) -> Expr {
+        !self.is_null()
+    }
+}
+
+impl std::ops::Not for Expr {
+    type Output = Expr;
+
+    fn not(self) -> Expr {
+        Expr::Not(Box::new(self))
+    }
+}
+
+impl Expr {
+    /// Parses the expression language described in the module docs.
+    pub fn parse(text: &str) -> Result<Expr> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            pos: 0,
+        };
+        let expr = parser.or()?;
+        parser.expect(&Token::End, "'and', 'or' or the end")?;
+        Ok(expr)
+    }
+
+    pub fn and(self, other: Expr) -> Expr {
+        Expr::And(Box::new(self), Box::new(other))
+    }
+
+    pub fn or(self, other: Expr) -> Expr {
+        Expr::Or(Box::new(self), Box::new(other))
+    }
+
+    /// Row mask for `table`, usable with [`Table::filter`].
+    pub fn mask(&self, table: &Table) -> Result<Vec<bool>> {
+        let bound = self.bind(&|name| table.column(name).ok().map(|c| c.dtype()))?;
+        Ok((0..table.n_rows())
+            .map(|row| bound.eval(&|name| table.column(name).map_or(Value::Null, |c| c.get(row))))
+            .collect())
+    }
+
+    /// Whether `record` satisfies the expression.
+    pub fn matches(&self, record: &SalaryRecord) -> Result<bool> {
+        let bound = self.bind(&record_type)?;
+        Ok(bound.eval(&|name| record.get(name).unwrap_or(Value::Null)))
+    }
+
+    /// One flag per record: whether it satisfies the expression.
+    pub fn mask_records(&self, dataset: &[SalaryRecord]) -> Result<Vec<bool>> {
+        let bound = self.bind(&record_type)?;
+        Ok(dataset
+            .iter()
+            .map(|record| bound.eval(&|name| record.get(name).unwrap_or(Value::Null)))
+            .collect())
+    }
+
+    /// The records that satisfy the expression, in order.
+    pub fn filter_records<'a>(&self, dataset: &'a [SalaryRecord]) -> Result<Vec<&'a SalaryRecord>> {
+        let mask = self.mask_records(dataset)?;
+        Ok(dataset
+            .iter()
+            .zip(mask)
+            .filter(|(_, hit)| *hit)
+            .map(|(record, _)| record)
+            .collect())
+    }
+
+    /// Checks columns and literal types, converting date literals, so that
+    /// evaluation cannot fail.
+    fn bind(&self, dtype_of: &dyn Fn(&str) -> Option<DataType>) -> Result<Expr> {
+        let dtype = |column: &str| match dtype_of(column) {
+            Some(dtype) => Ok(dtype),
+            None => bail!("unknown column '{}'", column),
+        };
+        let bound = match self {
+            Expr::Compare { column, op, value } => Expr::Compare {
+                column: column.clone(),
+                op: *op,
+                value: coerce(column, dtype(column)?, value)?,
+            },
+            Expr::In { column, values } | Expr::NotIn { column, values } => {
+                let dtype = dtype(column)?;
+                let values = values
+                    .iter()
+                    .map(|value| coerce(column, dtype, value))
+                    .collect::<Result<Vec<_>>>()?;
+                let column = col(column.clone());
+                match self {
+                    Expr::In { .. } => column.is_in(values),
+                    _ => column.not_in(values),
+                }
+            }
+            Expr::IsNull { column } => {
+                dtype(column)?;
+                self.clone()
+            }
+            Expr::Not(inner) => !inner.bind(dtype_of)?,
+            Expr::And(a, b) => a.bind(dtype_of)?.and(b.bind(dtype_of)?),
+            Expr::Or(a, b) => a.bind(dtype_of)?.or(b.bind(dtype_of)?),
+        };
+        Ok(bound)
+    }
+
+    fn eval(&self, cell: &dyn Fn(&str) -> Value) -> bool {
+        match self {
+            Expr::Compare { column, op, value } => match cell(column) {
+                found if is_missing(&found) => false,
+                found => op.holds(found.total_cmp(value)),
+            },
+            Expr::In { column, values } => match cell(column) {
+                found if is_missing(&found) => false,
+                found => values.iter().any(|v| found.total_cmp(v).is_eq()),
+            },
+            Expr::NotIn { column, values } => match cell(column) {
+                found if is_missing(&found) => false,
+                found => !values.iter().any(|v| found.total_cmp(v).is_eq()),
+            },
+            Expr::IsNull { column } => is_missing(&cell(column)),
+            Expr::Not(inner) => !inner.eval(cell),
+            Expr::And(a, b) => a.eval(cell) && b.eval(cell),
+            Expr::Or(a, b) => a.eval(cell) || b.eval(cell),
+        }
+    }
+}
+
+impl FromStr for Expr {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Expr> {
+        Expr::parse(text)
+    }
+}
+
+/// Prints the expression in the parseable text form, fully parenthesized.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Compare { column, op, value } => {
+                write!(f, "{} {} {}", column, op, Literal(value))
+            }
+            Expr::In { column, values } => {
+                let values: Vec<String> = values.iter().map(|v| Literal(v).to_string()).collect();
+                write!(f, "{} in ({})", column, values.join(", "))
+            }
+            Expr::NotIn { column, values } => {
+                let values: Vec<String> = values.iter().map(|v| Literal(v).to_string()).collect();
+                write!(f, "{} not in ({})", column, values.join(", "))
+            }
+            Expr::IsNull { column } => write!(f, "{} is null", column),
+            Expr::Not(inner) => write!(f, "not ({})", inner),
+            Expr::And(a, b) => write!(f, "({}) and ({})", a, b),
+            Expr::Or(a, b) => write!(f, "({}) or ({})", a, b),
+        }
+    }
+}
+
+struct Literal<'a>(&'a Value);
+
+impl fmt::Display for Literal<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Value::Str(s) => write!(f, "\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+            Value::Date(d) => write!(f, "\"{}\"", d),
+            Value::Float(v) if v.fract() == 0.0 && v.is_finite() => write!(f, "{:.1}", v),
+            other => write!(f, "{}", other),
+        }
+    }
+}
+
+/// Missing cells and NaN never satisfy a comparison; `is null` finds them.
+fn is_missing(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Float(v) => v.is_nan(),
+        _ => false,
+    }
+}
+
+fn record_type(name: &str) -> Option<DataType> {
+    SalaryRecord::FIELDS
+        .iter()
+        .find(|(field, _)| *field == name)
+        .map(|&(_, dtype)| dtype)
+}
+
+fn literal_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Float(_) => "float",
+        Value::Int(_) => "int",
+        Value::Str(_) => "str",
+        Value::Bool(_) => "bool",
+        Value::Date(_) => "date",
+    }
+}
+
+/// `value` as a literal for a `dtype` column, or a type mismatch error.
+fn coerce(column: &str, dtype: DataType, value: &Value) -> Result<Value> {
+    if let Value::Float(v) = value {
+        if !v.is_finite() {
+            bail!("column '{}': literal {} is not a finite number", column, v);
+        }
+    }
+    let coerced = match (dtype, value) {
+        (DataType::Int | DataType::Float, Value::Int(_) | Value::Float(_))
+        | (DataType::Str, Value::Str(_))
+        | (DataType::Bool, Value::Bool(_))
+        | (DataType::Date, Value::Date(_)) => Some(value.clone()),
+        (DataType::Date, Value::Str(text)) => crate::schema::parse_date(text).map(Value::Date),
+        _ => None,
+    };
+    match coerced {
+        Some(value) => Ok(value),
+        None => bail!(
+            "type mismatch: column '{}' is {}, cannot compare with {} {}",
+            column,
+            dtype,
+            literal_type(value),
+            Literal(value)
+        ),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Number(String),
+    Op(CmpOp),
+    LParen,
+    RParen,
+    Comma,
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(s) | Token::Number(s) => write!(f, "'{}'", s),
+            Token::Str(s) => write!(f, "{:?}", s),
+            Token::Op(op) => write!(f, "'{}'", op),
+            Token::LParen => f.write_str("'('"),
+            Token::RParen => f.write_str("')'"),
+            Token::Comma => f.write_str("','"),
+            Token::End => f.write_str("the end"),
+        }
+    }
+}
+
+/// Tokens with their 1-based character position.
+fn tokenize(text: &str) -> Result<Vec<(Token, usize)>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        let token = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            ',' => Token::Comma,
+            '"' | '\'' => {
+                let mut text = String::new();
+                loop {
+                    i += 1;
+                    match chars.get(i) {
+                        None => bail!("unterminated string starting at position {}", start + 1),
+                        Some(&d) if d == c => break,
+                        Some('\\') if i + 1 < chars.len() => {
+                            i += 1;
+                            text.push(chars[i]);
+                        }
+                        Some(&d) => text.push(d),
+                    }
+                }
+                Token::Str(text)
+            }
+            '=' | '!' | '<' | '>' => {
+                let next = chars.get(i + 1).copied();
+                let (op, len) = match (c, next) {
+                    ('=', Some('=')) => (CmpOp::Eq, 2),
+                    ('=', _) => (CmpOp::Eq, 1),
+                    ('!', Some('=')) => (CmpOp::Ne, 2),
+                    ('<', Some('>')) => (CmpOp::Ne, 2),
+                    ('<', Some('=')) => (CmpOp::Le, 2),
+                    ('<', _) => (CmpOp::Lt, 1),
+                    ('>', Some('=')) => (CmpOp::Ge, 2),
+                    ('>', _) => (CmpOp::Gt, 1),
+                    _ => bail!("unexpected '{}' at position {}", c, start + 1),
+                };
+                i += len - 1;
+                Token::Op(op)
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '.' => {
+                while let Some(&d) = chars.get(i + 1) {
+                    let exponent_sign = matches!(d, '+' | '-') && matches!(chars[i], 'e' | 'E');
+                    if d.is_ascii_digit() || matches!(d, '.' | 'e' | 'E') || exponent_sign {
+                        i += 1;
+                    } else {
+                        break;
+                    }
+                }
+                Token::Number(chars[start..=i].iter().collect())
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                while chars
+                    .get(i + 1)
+                    .is_some_and(|d| d.is_alphanumeric() || *d == '_')
+                {
+                    i += 1;
+                }
+                Token::Ident(chars[start..=i].iter().collect())
+            }
+            other => bail!("unexpected '{}' at position {}", other, start + 1),
+        };
+        tokens.push((token, start + 1));
+        i += 1;
+    }
+    tokens.push((Token::End, chars.len() + 1));
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].0.clone();
+        if token != Token::End {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn error<T>(&self, expected: &str) -> Result<T> {
+        let (found, position) = &self.tokens[self.pos];
+        bail!(
+            "expected {} at position {}, found {}",
+            expected,
+            position,
+            found
+        )
+    }
+
+    fn expect(&mut self, token: &Token, expected: &str) -> Result<()> {
+        if self.peek() != token {
+            return self.error(expected);
+        }
+        self.advance();
+        Ok(())
+    }
+
+    fn keyword(&mut self, word: &str) -> bool {
+        match self.peek() {
+            Token::Ident(ident) if ident.eq_ignore_ascii_case(word) => {
+                self.advance();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut expr = self.and()?;
+        while self.keyword("or") {
+            expr = expr.or(self.and()?);
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+        while self.keyword("and") {
+            expr = expr.and(self.unary()?);
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.keyword("not") {
+            return Ok(!self.unary()?);
+        }
+        if self.peek() == &Token::LParen {
+            self.advance();
+            let expr = self.or()?;
+            self.expect(&Token::RParen, "')'")?;
+            return Ok(expr);
+        }
+        let column = match self.peek() {
+            Token::Ident(ident) if !is_keyword(ident) => ident.clone(),
+            _ => return self.error("a column name"),
+        };
+        self.advance();
+        self.predicate(col(column))
+    }
+
+    fn predicate(&mut self, column: Col) -> Result<Expr> {
+        if let Token::Op(op) = *self.peek() {
+            self.advance();
+            return Ok(column.compare(op, self.literal()?));
+        }
+        if self.keyword("is") {
+            let negated = self.keyword("not");
+            if !self.keyword("null") {
+                return self.error("'null'");
+            }
+            return Ok(if negated {
+                column.is_not_null()
+            } else {
+                column.is_null()
+            });
+        }
+        let negated = self.keyword("not");
+        if !self.keyword("in") {
+            return self.error(if negated {
+                "'in'"
+            } else {
+                "a comparison, 'in' or 'is'"
+            });
+        }
+        self.expect(&Token::LParen, "'('")?;
+        let mut values = vec![self.literal()?];
+        while self.peek() == &Token::Comma {
+            self.advance();
+            values.push(self.literal()?);
+        }
+        self.expect(&Token::RParen, "',' or ')'")?;
+        Ok(if negated {
+            column.not_in(values)
+        } else {
+            column.is_in(values)
+        })
+    }
+
+    fn literal(&mut self) -> Result<Value> {
+        let value = match self.peek() {
+            Token::Str(text) => Value::Str(text.clone()),
+            Token::Number(text) => match (text.parse::<i64>(), text.parse::<f64>()) {
+                (Ok(int), _) => Value::Int(int),
+                (_, Ok(float)) if float.is_finite() => Value::Float(float),
+                _ => return self.error("a finite number"),
+            },
+            Token::Ident(word) if word.eq_ignore_ascii_case("true") => Value::Bool(true),
+            Token::Ident(word) if word.eq_ignore_ascii_case("false") => Value::Bool(false),
+            _ => return self.error("a value"),
+        };
+        self.advance();
+        Ok(value)
+    }
+}
+
+fn is_keyword(word: &str) -> bool {
+    ["and", "or", "not", "in", "is", "null", "true", "false"]
+        .iter()
+        .any(|keyword| word.eq_ignore_ascii_case(keyword))
+}
+
+impl Table {
+    /// Keeps the rows where `expr` holds.
+    pub fn filter_expr(&self, expr: &Expr) -> Result<Table> {
+        self.filter(&expr.mask(self)?)
+    }
+}
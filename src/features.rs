@@ -0,0 +1,301 @@
+use crate::categories::{Category, ExperienceLevel};
+use crate::dataset::SalaryRecord;
+use crate::descriptive::{
+    calc_mean, calc_mean_with, calc_std, calc_std_with, MissingPolicy, RunningStats,
+};
+use crate::expr::Expr;
+use crate::table::{Column, Table, Value};
+use anyhow::{bail, Context, Result};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// Z-scores of `salary_in_usd`. Missing salaries are left out of the mean
+/// and std and stay missing in the output.
+pub fn standardize_salary(dataset: &[SalaryRecord]) -> Result<Vec<Option<f32>>> {
+    let salaries: Vec<Option<f32>> = dataset.iter().map(|record| record.salary_in_usd).collect();
+    let mean = calc_mean_with(&salaries, MissingPolicy::Skip)?.value;
+    let std = calc_std_with(&salaries, MissingPolicy::Skip)?.value;
+    let std_col = salaries
+        .iter()
+        .map(|salary| salary.map(|salary| (salary - mean) / std))
+        .collect();
+    Ok(std_col)
+}
+
+/// Streaming [`standardize_salary`].
+///
+/// Z-scores need the mean and std up front, so `stats` must come from an
+/// earlier pass over the same data (see [`RunningStats`]).
+pub fn stream_standardize_salary<I>(
+    records: I,
+    stats: &RunningStats,
+) -> Result<impl Iterator<Item = Result<Option<f32>>>>
+where
+    I: IntoIterator<Item = Result<SalaryRecord>>,
+{
+    let mean = stats.mean()?;
+    let std = stats.std()?;
+    Ok(records.into_iter().map(move |record| {
+        record.map(|record| record.salary_in_usd.map(|salary| (salary - mean) / std))
+    }))
+}
+
+/// Assigns a column index to every distinct job title.
+pub fn create_job_title_map(dataset: &[SalaryRecord]) -> Result<HashMap<String, usize>> {
+    let job_title_set: HashSet<String> = dataset
+        .iter()
+        .map(|record| record.job_title.clone())
+        .collect();
+    Ok(index_job_titles(job_title_set))
+}
+
+/// Streaming [`create_job_title_map`]; memory grows with the number of
+/// distinct titles, not with the number of records.
+pub fn stream_job_title_map<I>(records: I) -> Result<HashMap<String, usize>>
+where
+    I: IntoIterator<Item = Result<SalaryRecord>>,
+{
+    let mut job_title_set: HashSet<String> = HashSet::new();
+    for record in records {
+        job_title_set.insert(record?.job_title);
+    }
+    Ok(index_job_titles(job_title_set))
+}
+
+fn index_job_titles(mut job_title_set: HashSet<String>) -> HashMap<String, usize> {
+    let mut job_title_mapping: HashMap<String, usize> = HashMap::new();
+    for (i, job_title) in job_title_set.drain().enumerate() {
+        job_title_mapping.insert(job_title, i);
+    }
+    job_title_mapping
+}
+
+/// One-hot rows for `job_title`, laid out according to `mapping`.
+///
+/// A title missing from `mapping` is an error naming the record, e.g. when
+/// a test split holds a title the training split never saw.
+pub fn one_hot_encode_job_titles(
+    dataset: &[SalaryRecord],
+    mapping: &HashMap<String, usize>,
+) -> Result<Vec<Vec<i32>>> {
+    dataset
+        .iter()
+        .enumerate()
+        .map(|(i, record)| {
+            one_hot_job_title(record, mapping).with_context(|| format!("record {}", i))
+        })
+        .collect()
+}
+
+/// Streaming [`one_hot_encode_job_titles`].
+pub fn stream_one_hot_encode_job_titles<'a, I>(
+    records: I,
+    mapping: &'a HashMap<String, usize>,
+) -> impl Iterator<Item = Result<Vec<i32>>> + 'a
+where
+    I: IntoIterator<Item = Result<SalaryRecord>>,
+    I::IntoIter: 'a,
+{
+    records.into_iter().enumerate().map(move |(i, record)| {
+        one_hot_job_title(&record?, mapping).with_context(|| format!("record {}", i))
+    })
+}
+
+/// Named `Int` columns for the rows from [`one_hot_encode_job_titles`],
+/// one per job title in `mapping` order, ready for export.
+pub fn job_title_table(rows: &[Vec<i32>], mapping: &HashMap<String, usize>) -> Result<Table> {
+    let mut titles: Vec<(&String, usize)> = mapping.iter().map(|(t, &i)| (t, i)).collect();
+    titles.sort_by_key(|&(_, i)| i);
+
+    let mut table = Table::new();
+    for (title, index) in titles {
+        let column = rows
+            .iter()
+            .map(|row| match row.get(index) {
+                Some(&hit) => Ok(Some(hit as i64)),
+                None => bail!("row has {} columns, mapping needs {}", row.len(), index + 1),
+            })
+            .collect::<Result<_>>()?;
+        table.add_column(title.as_str(), Column::Int(column))?;
+    }
+    Ok(table)
+}
+
+fn one_hot_job_title(record: &SalaryRecord, mapping: &HashMap<String, usize>) -> Result<Vec<i32>> {
+    let Some(&index) = mapping.get(&record.job_title) else {
+        bail!("job title '{}' is not in the mapping", record.job_title);
+    };
+    let mut encoding = vec![0; mapping.len()];
+    encoding[index] = 1;
+    Ok(encoding)
+}
+
+/// Dummy column: 1 when the company is located in the US, 0 otherwise
+/// (including unknown locations).
+pub fn create_us_based_feature(dataset: &[SalaryRecord]) -> Result<Vec<usize>> {
+    let res: Vec<usize> = dataset.iter().map(us_based).collect();
+    Ok(res)
+}
+
+/// Streaming [`create_us_based_feature`].
+pub fn stream_us_based_feature<I>(records: I) -> impl Iterator<Item = Result<usize>>
+where
+    I: IntoIterator<Item = Result<SalaryRecord>>,
+{
+    records
+        .into_iter()
+        .map(|record| record.map(|record| us_based(&record)))
+}
+
+fn us_based(record: &SalaryRecord) -> usize {
+    if record.company_location.as_deref() == Some("US") {
+        1
+    } else {
+        0
+    }
+}
+
+/// Dummy column for any condition: 1 where `expr` holds, 0 otherwise.
+/// [`create_us_based_feature`] is `company_location = "US"`.
+pub fn create_feature_where(dataset: &[SalaryRecord], expr: &Expr) -> Result<Vec<usize>> {
+    let mask = expr.mask_records(dataset)?;
+    Ok(mask.into_iter().map(usize::from).collect())
+}
+
+/// Senior-level rows as `(work_year, job_title, rounded salary_in_usd)`.
+pub fn filter_and_convert(dataset: &[SalaryRecord]) -> Result<Vec<(i32, String, Option<f32>)>> {
+    let data = dataset
+        .iter()
+        .filter(|record| is_senior(record))
+        .map(convert)
+        .collect();
+    Ok(data)
+}
+
+/// Streaming [`filter_and_convert`]. Parse errors are passed through.
+pub fn stream_filter_and_convert<I>(
+    records: I,
+) -> impl Iterator<Item = Result<(i32, String, Option<f32>)>>
+where
+    I: IntoIterator<Item = Result<SalaryRecord>>,
+{
+    records.into_iter().filter_map(|record| match record {
+        Ok(record) if is_senior(&record) => Some(Ok(convert(&record))),
+        Ok(_) => None,
+        Err(error) => Some(Err(error)),
+    })
+}
+
+/// [`filter_and_convert`] for any condition instead of senior level, e.g.
+/// `experience_level in ("SE", "EX") and salary_in_usd > 100000`.
+pub fn filter_and_convert_where(
+    dataset: &[SalaryRecord],
+    expr: &Expr,
+) -> Result<Vec<(i32, String, Option<f32>)>> {
+    let data = expr
+        .filter_records(dataset)?
+        .into_iter()
+        .map(convert)
+        .collect();
+    Ok(data)
+}
+
+fn is_senior(record: &SalaryRecord) -> bool {
+    record.experience_level == ExperienceLevel::Senior
+}
+
+fn convert(record: &SalaryRecord) -> (i32, String, Option<f32>) {
+    let salary_in_usd_rounded = record.salary_in_usd.map(f32::round);
+    (
+        record.work_year,
+        record.job_title.to_string(),
+        salary_in_usd_rounded,
+    )
+}
+
+/// One-hot rows for a categorical field, one column per variant of `C`
+/// in natural order. Unlike job titles, no mapping needs to be built.
+pub fn one_hot_encode_category<C: Category>(values: &[C]) -> Result<Vec<Vec<i32>>> {
+    let res = values
+        .iter()
+        .map(|value| {
+            let mut encoding = vec![0; C::ALL.len()];
+            encoding[value.index()] = 1;
+            encoding
+        })
+        .collect();
+    Ok(res)
+}
+
+/// Ordinal codes (0, 1, 2, ...) for a categorical field, following the
+/// natural order of `C`, e.g. EN=0 < MI=1 < SE=2 < EX=3.
+pub fn ordinal_encode<C: Category>(values: &[C]) -> Result<Vec<usize>> {
+    Ok(values.iter().map(|value| value.index()).collect())
+}
+
+/// Groups records by any key, such as one of the categorical enums.
+/// Groups come out sorted by key.
+pub fn group_records_by<K: Ord>(
+    dataset: &[SalaryRecord],
+    key: impl Fn(&SalaryRecord) -> K,
+) -> Result<BTreeMap<K, Vec<&SalaryRecord>>> {
+    let mut groups: BTreeMap<K, Vec<&SalaryRecord>> = BTreeMap::new();
+    for record in dataset {
+        groups.entry(key(record)).or_default().push(record);
+    }
+    Ok(groups)
+}
+
+/// Z-scores of any numeric column. Missing cells stay missing.
+pub fn standardize_column(column: &Column) -> Result<Column> {
+    let cells = column.to_f64()?;
+    let values: Vec<f64> = cells.iter().flatten().copied().collect();
+    if values.len() < 2 {
+        bail!("standardizing needs at least two non-missing values");
+    }
+    let mean = calc_mean(&values)?;
+    let std = calc_std(&values)?;
+    Ok(Column::Float(
+        cells
+            .iter()
+            .map(|cell| cell.map(|v| (v - mean) / std))
+            .collect(),
+    ))
+}
+
+/// One `Int` 0/1 column per distinct value of `column`, named after the
+/// value and sorted. Missing cells are all zeros.
+pub fn one_hot_encode(column: &Column) -> Result<Table> {
+    let levels: BTreeSet<String> = (0..column.len())
+        .map(|i| column.get(i))
+        .filter(|value| *value != Value::Null)
+        .map(|value| value.to_string())
+        .collect();
+
+    let mut table = Table::new();
+    for level in levels {
+        let dummy = indicator(column, &level)?;
+        table.add_column(level, dummy)?;
+    }
+    Ok(table)
+}
+
+/// `Int` column with 1 where `column` equals `value` (compared as text) and
+/// 0 elsewhere, including missing cells.
+pub fn indicator(column: &Column, value: &str) -> Result<Column> {
+    let mask = equals_mask(column, value)?;
+    Ok(Column::Int(
+        mask.into_iter().map(|hit| Some(hit as i64)).collect(),
+    ))
+}
+
+/// Row mask for `column == value` (compared as text), usable with
+/// [`Table::filter`].
+pub fn equals_mask(column: &Column, value: &str) -> Result<Vec<bool>> {
+    let mask = (0..column.len())
+        .map(|i| match column.get(i) {
+            Value::Null => false,
+            cell => cell.to_string() == value,
+        })
+        .collect();
+    Ok(mask)
+}
@@ -0,0 +1,509 @@
+use crate::dataset::{load_from_reader, SalaryRecord};
+use reqwest::header::{
+    HeaderMap, CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::StatusCode;
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Content types accepted by default: CSV, possibly compressed. GitHub raw
+/// files come back as `text/plain`.
+const DEFAULT_CONTENT_TYPES: [&str; 9] = [
+    "text/csv",
+    "text/plain",
+    "application/csv",
+    "application/octet-stream",
+    "application/gzip",
+    "application/x-gzip",
+    "application/zstd",
+    "application/zip",
+    "application/x-zip-compressed",
+];
+
+/// Why a fetch failed.
+#[derive(Debug)]
+pub enum FetchError {
+    /// Connection, DNS, TLS or timeout failure, after every retry.
+    Network { url: String, source: reqwest::Error },
+    /// The server answered with a non-success status, after every retry.
+    Http { url: String, status: StatusCode },
+    /// A body arrived but was rejected: wrong content type or checksum.
+    Invalid { url: String, reason: String },
+    /// The body could not be parsed as a dataset.
+    Parse {
+        url: String,
+        source: Box<dyn Error + Send + Sync>,
+    },
+    /// Offline mode was asked for and the URL is not cached.
+    Offline { url: String },
+    /// The cache could not be written.
+    Cache { url: String, source: io::Error },
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Network { url, source } => {
+                write!(f, "failed to fetch {}: {}", url, source)
+            }
+            FetchError::Http { url, status } => write!(f, "fetching {} returned {}", url, status),
+            FetchError::Invalid { url, reason } => write!(f, "fetching {}: {}", url, reason),
+            FetchError::Parse { url, source } => write!(f, "failed to parse {}: {}", url, source),
+            FetchError::Offline { url } => {
+                write!(f, "offline mode: {} is not in the cache", url)
+            }
+            FetchError::Cache { url, source } => {
+                write!(f, "failed to cache {}: {}", url, source)
+            }
+        }
+    }
+}
+
+impl Error for FetchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FetchError::Network { source, .. } => Some(source),
+            FetchError::Parse { source, .. } => Some(source.as_ref()),
+            FetchError::Cache { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// How [`fetch_with`] and [`fetch_async`] talk to the network and the
+/// on-disk cache.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchOptions {
+    /// Directory holding one cached copy per URL. No caching when `None`.
+    pub cache_dir: Option<PathBuf>,
+    /// Expected SHA-256 of the body, as lowercase hex.
+    pub sha256: Option<String>,
+    /// Serve only from the cache and never touch the network.
+    pub offline: bool,
+    /// Limit for a whole request, connection to last byte.
+    pub timeout: Duration,
+    /// Extra attempts after a network error, `429` or `5xx`.
+    pub retries: u32,
+    /// Wait before the first retry; doubled for every retry after that.
+    pub backoff: Duration,
+    /// Accepted `Content-Type` media types. Empty accepts anything; a
+    /// response without the header is always accepted.
+    pub content_types: Vec<String>,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        FetchOptions {
+            cache_dir: None,
+            sha256: None,
+            offline: false,
+            timeout: Duration::from_secs(30),
+            retries: 2,
+            backoff: Duration::from_millis(500),
+            content_types: DEFAULT_CONTENT_TYPES.map(String::from).to_vec(),
+        }
+    }
+}
+
+impl FetchOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Pins the dataset: any body with a different SHA-256 is rejected,
+    /// whether it comes from the network or the cache.
+    pub fn sha256(mut self, hex: impl Into<String>) -> Self {
+        self.sha256 = Some(hex.into().to_lowercase());
+        self
+    }
+
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.retries = retries;
+        self.backoff = backoff;
+        self
+    }
+
+    pub fn content_types<S: Into<String>>(mut self, types: impl IntoIterator<Item = S>) -> Self {
+        self.content_types = types.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// How long to wait after `error` on attempt `attempt` (counting from
+    /// 0), or `None` when the error is final or the retries are used up.
+    /// Network errors, `429` and `5xx` are worth another attempt.
+    fn retry_delay(&self, attempt: u32, error: &FetchError) -> Option<Duration> {
+        let transient = match error {
+            FetchError::Network { .. } => true,
+            FetchError::Http { status, .. } => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            _ => false,
+        };
+        (transient && attempt < self.retries)
+            .then(|| self.backoff.saturating_mul(2u32.saturating_pow(attempt)))
+    }
+}
+
+/// Lowercase hex SHA-256 of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Cached body plus the validators the server sent with it.
+struct CacheEntry {
+    body: Vec<u8>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+struct Cache {
+    body: PathBuf,
+    meta: PathBuf,
+}
+
+impl Cache {
+    fn new(dir: &Path, url: &str) -> Self {
+        let key = sha256_hex(url.as_bytes());
+        Cache {
+            body: dir.join(format!("{}.body", key)),
+            meta: dir.join(format!("{}.meta", key)),
+        }
+    }
+
+    fn load(&self) -> Option<CacheEntry> {
+        let body = fs::read(&self.body).ok()?;
+        let meta = fs::read_to_string(&self.meta).unwrap_or_default();
+        let header = |name: &str| {
+            meta.lines()
+                .find_map(|line| line.strip_prefix(name)?.strip_prefix('\t'))
+                .map(str::to_string)
+        };
+        Some(CacheEntry {
+            body,
+            etag: header("etag"),
+            last_modified: header("last-modified"),
+        })
+    }
+
+    fn store(&self, entry: &CacheEntry) -> io::Result<()> {
+        if let Some(dir) = self.body.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut meta = String::new();
+        if let Some(etag) = &entry.etag {
+            meta.push_str(&format!("etag\t{}\n", etag));
+        }
+        if let Some(last_modified) = &entry.last_modified {
+            meta.push_str(&format!("last-modified\t{}\n", last_modified));
+        }
+        fs::write(&self.body, &entry.body)?;
+        fs::write(&self.meta, meta)
+    }
+}
+
+/// State shared by the blocking and async front-ends: everything except
+/// sending the request and sleeping between retries.
+struct Fetch<'a> {
+    url: &'a str,
+    options: &'a FetchOptions,
+    cache: Option<Cache>,
+    cached: Option<CacheEntry>,
+}
+
+enum Start<'a> {
+    /// Answered from the cache without a request (offline mode).
+    Cached(Vec<u8>),
+    Request(Fetch<'a>),
+}
+
+/// What to do with a response, judging by its status line and headers.
+enum Step {
+    NotModified,
+    ReadBody,
+}
+
+impl<'a> Fetch<'a> {
+    fn start(url: &'a str, options: &'a FetchOptions) -> Result<Start<'a>, FetchError> {
+        let cache = options.cache_dir.as_deref().map(|dir| Cache::new(dir, url));
+        // A cached copy that fails the pin is as good as no copy.
+        let cached = cache
+            .as_ref()
+            .and_then(Cache::load)
+            .filter(|entry| verify(url, &entry.body, options).is_ok());
+
+        if options.offline {
+            return match cached {
+                Some(entry) => Ok(Start::Cached(entry.body)),
+                None => Err(FetchError::Offline {
+                    url: url.to_string(),
+                }),
+            };
+        }
+        Ok(Start::Request(Fetch {
+            url,
+            options,
+            cache,
+            cached,
+        }))
+    }
+
+    /// Conditional request headers for the cached copy, if any.
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(entry) = &self.cached {
+            if let Some(etag) = entry.etag.as_deref().and_then(|v| v.parse().ok()) {
+                headers.insert(IF_NONE_MATCH, etag);
+            }
+            if let Some(stamp) = entry.last_modified.as_deref().and_then(|v| v.parse().ok()) {
+                headers.insert(IF_MODIFIED_SINCE, stamp);
+            }
+        }
+        headers
+    }
+
+    /// Whether a failure is worth retrying is up to
+    /// [`FetchOptions::retry_delay`].
+    fn step(&self, status: StatusCode, headers: &HeaderMap) -> Result<Step, FetchError> {
+        if status == StatusCode::NOT_MODIFIED && self.cached.is_some() {
+            return Ok(Step::NotModified);
+        }
+        if !status.is_success() {
+            return Err(self.http_error(status));
+        }
+        self.check_content_type(headers)?;
+        Ok(Step::ReadBody)
+    }
+
+    fn check_content_type(&self, headers: &HeaderMap) -> Result<(), FetchError> {
+        let Some(value) = headers.get(CONTENT_TYPE) else {
+            return Ok(());
+        };
+        let media_type = value
+            .to_str()
+            .unwrap_or_default()
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+        let allowed = &self.options.content_types;
+        if allowed.is_empty() || allowed.iter().any(|t| t.eq_ignore_ascii_case(&media_type)) {
+            Ok(())
+        } else {
+            Err(FetchError::Invalid {
+                url: self.url.to_string(),
+                reason: format!("unexpected content type '{}'", media_type),
+            })
+        }
+    }
+
+    fn http_error(&self, status: StatusCode) -> FetchError {
+        FetchError::Http {
+            url: self.url.to_string(),
+            status,
+        }
+    }
+
+    fn network_error(&self, source: reqwest::Error) -> FetchError {
+        FetchError::Network {
+            url: self.url.to_string(),
+            source,
+        }
+    }
+
+    /// The cached body, after a `304 Not Modified`.
+    fn reuse(self) -> Vec<u8> {
+        self.cached.map(|entry| entry.body).unwrap_or_default()
+    }
+
+    /// Verifies a fresh body and stores it in the cache.
+    fn finish(self, headers: &HeaderMap, body: Vec<u8>) -> Result<Vec<u8>, FetchError> {
+        verify(self.url, &body, self.options)?;
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let entry = CacheEntry {
+            body,
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        };
+        if let Some(cache) = &self.cache {
+            cache.store(&entry).map_err(|source| FetchError::Cache {
+                url: self.url.to_string(),
+                source,
+            })?;
+        }
+        Ok(entry.body)
+    }
+}
+
+fn verify(url: &str, body: &[u8], options: &FetchOptions) -> Result<(), FetchError> {
+    if let Some(expected) = &options.sha256 {
+        let actual = sha256_hex(body);
+        if &actual != expected {
+            return Err(FetchError::Invalid {
+                url: url.to_string(),
+                reason: format!("SHA-256 mismatch: expected {}, got {}", expected, actual),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Downloads `url` with the blocking client, going through the cache,
+/// retries and checks configured in `options`.
+///
+/// With a cache, a stored copy is revalidated with `If-None-Match` /
+/// `If-Modified-Since` and reused on `304 Not Modified`.
+pub fn fetch_with(url: &str, options: &FetchOptions) -> Result<Vec<u8>, FetchError> {
+    let fetch = match Fetch::start(url, options)? {
+        Start::Cached(body) => return Ok(body),
+        Start::Request(fetch) => fetch,
+    };
+    let client = reqwest::blocking::Client::builder()
+        .timeout(options.timeout)
+        .build()
+        .map_err(|error| fetch.network_error(error))?;
+
+    let mut attempt = 0;
+    loop {
+        let error = match client.get(url).headers(fetch.headers()).send() {
+            Err(error) => fetch.network_error(error),
+            Ok(response) => match fetch.step(response.status(), response.headers()) {
+                Err(error) => error,
+                Ok(Step::NotModified) => return Ok(fetch.reuse()),
+                Ok(Step::ReadBody) => {
+                    let headers = response.headers().clone();
+                    match response.bytes() {
+                        Ok(body) => return fetch.finish(&headers, body.to_vec()),
+                        Err(error) => fetch.network_error(error),
+                    }
+                }
+            },
+        };
+        match options.retry_delay(attempt, &error) {
+            Some(delay) => std::thread::sleep(delay),
+            None => return Err(error),
+        }
+        attempt += 1;
+    }
+}
+
+/// Opens `url` with the blocking client and streams the body once the
+/// status and content type pass, retrying only until then.
+///
+/// A SHA-256 pin or a cache needs the whole body, so with either option
+/// this downloads through [`fetch_with`] and reads from memory instead.
+pub fn fetch_reader(url: &str, options: &FetchOptions) -> Result<Box<dyn Read>, FetchError> {
+    if options.sha256.is_some() || options.cache_dir.is_some() {
+        return Ok(Box::new(io::Cursor::new(fetch_with(url, options)?)));
+    }
+    let fetch = match Fetch::start(url, options)? {
+        Start::Cached(body) => return Ok(Box::new(io::Cursor::new(body))),
+        Start::Request(fetch) => fetch,
+    };
+    let client = reqwest::blocking::Client::builder()
+        .timeout(options.timeout)
+        .build()
+        .map_err(|error| fetch.network_error(error))?;
+
+    let mut attempt = 0;
+    loop {
+        let error = match client.get(url).headers(fetch.headers()).send() {
+            Err(error) => fetch.network_error(error),
+            Ok(response) => match fetch.step(response.status(), response.headers()) {
+                Err(error) => error,
+                Ok(Step::NotModified) => return Ok(Box::new(io::Cursor::new(fetch.reuse()))),
+                Ok(Step::ReadBody) => return Ok(Box::new(response)),
+            },
+        };
+        match options.retry_delay(attempt, &error) {
+            Some(delay) => std::thread::sleep(delay),
+            None => return Err(error),
+        }
+        attempt += 1;
+    }
+}
+
+/// Async twin of [`fetch_with`], for use under a Tokio runtime.
+pub async fn fetch_async(url: &str, options: &FetchOptions) -> Result<Vec<u8>, FetchError> {
+    let fetch = match Fetch::start(url, options)? {
+        Start::Cached(body) => return Ok(body),
+        Start::Request(fetch) => fetch,
+    };
+    let client = reqwest::Client::builder()
+        .timeout(options.timeout)
+        .build()
+        .map_err(|error| fetch.network_error(error))?;
+
+    let mut attempt = 0;
+    loop {
+        let error = match client.get(url).headers(fetch.headers()).send().await {
+            Err(error) => fetch.network_error(error),
+            Ok(response) => match fetch.step(response.status(), response.headers()) {
+                Err(error) => error,
+                Ok(Step::NotModified) => return Ok(fetch.reuse()),
+                Ok(Step::ReadBody) => {
+                    let headers = response.headers().clone();
+                    match response.bytes().await {
+                        Ok(body) => return fetch.finish(&headers, body.to_vec()),
+                        Err(error) => fetch.network_error(error),
+                    }
+                }
+            },
+        };
+        match options.retry_delay(attempt, &error) {
+            Some(delay) => tokio::time::sleep(delay).await,
+            None => return Err(error),
+        }
+        attempt += 1;
+    }
+}
+
+/// Fetches `url` and parses it as salary records.
+pub fn fetch_records(url: &str, options: &FetchOptions) -> Result<Vec<SalaryRecord>, FetchError> {
+    let body = fetch_with(url, options)?;
+    parse_records(url, &body)
+}
+
+/// Async twin of [`fetch_records`].
+pub async fn fetch_records_async(
+    url: &str,
+    options: &FetchOptions,
+) -> Result<Vec<SalaryRecord>, FetchError> {
+    let body = fetch_async(url, options).await?;
+    parse_records(url, &body)
+}
+
+fn parse_records(url: &str, body: &[u8]) -> Result<Vec<SalaryRecord>, FetchError> {
+    load_from_reader(body).map_err(|error| FetchError::Parse {
+        url: url.to_string(),
+        source: error.into(),
+    })
+}
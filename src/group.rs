@@ -0,0 +1,235 @@
+//! `group_by(keys).agg(...)` over [`Table`] columns.
+//!
+//! For salary benchmarks per role and year, group a
+//! [`Table::from_records`] view by `job_title` and `work_year` and
+//! aggregate `salary_in_usd` with e.g. [`Agg::median`] and
+//! [`Agg::quantile`]. The result is a tidy table: the key columns, sorted,
+//! then one column per aggregation, one row per group.
+
+use crate::descriptive::{calc_mean, calc_std, quantile_sorted, NeumaierSum};
+use crate::table::{Column, Table, ValueKey};
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+
+/// What an [`Agg`] computes over the non-missing values of a group.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AggFunc {
+    /// Number of rows in the group, missing values included.
+    Size,
+    /// Number of non-missing values.
+    Count,
+    Mean,
+    Median,
+    /// Sample standard deviation (n - 1).
+    Std,
+    Min,
+    Max,
+    Sum,
+    /// Linearly interpolated quantile, `0.0..=1.0`.
+    Quantile(f64),
+}
+
+/// One output column of [`GroupBy::agg`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Agg {
+    /// Input column; unused for [`AggFunc::Size`].
+    pub column: String,
+    pub func: AggFunc,
+    /// Output column name; defaults to e.g. `salary_in_usd_mean`.
+    pub alias: Option<String>,
+}
+
+impl Agg {
+    pub fn new(column: impl Into<String>, func: AggFunc) -> Self {
+        Agg {
+            column: column.into(),
+            func,
+            alias: None,
+        }
+    }
+
+    /// Rows per group, as the column `size`.
+    pub fn size() -> Self {
+        Agg::new("", AggFunc::Size)
+    }
+
+    pub fn count(column: impl Into<String>) -> Self {
+        Agg::new(column, AggFunc::Count)
+    }
+
+    pub fn mean(column: impl Into<String>) -> Self {
+        Agg::new(column, AggFunc::Mean)
+    }
+
+    pub fn median(column: impl Into<String>) -> Self {
+        Agg::new(column, AggFunc::Median)
+    }
+
+    pub fn std(column: impl Into<String>) -> Self {
+        Agg::new(column, AggFunc::Std)
+    }
+
+    pub fn min(column: impl Into<String>) -> Self {
+        Agg::new(column, AggFunc::Min)
+    }
+
+    pub fn max(column: impl Into<String>) -> Self {
+        Agg::new(column, AggFunc::Max)
+    }
+
+    pub fn sum(column: impl Into<String>) -> Self {
+        Agg::new(column, AggFunc::Sum)
+    }
+
+    pub fn quantile(column: impl Into<String>, q: f64) -> Self {
+        Agg::new(column, AggFunc::Quantile(q))
+    }
+
+    /// Names the output column.
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    /// The output column name.
+    pub fn name(&self) -> String {
+        if let Some(alias) = &self.alias {
+            return alias.clone();
+        }
+        let suffix = match self.func {
+            AggFunc::Size => return "size".to_string(),
+            AggFunc::Count => "count".to_string(),
+            AggFunc::Mean => "mean".to_string(),
+            AggFunc::Median => "median".to_string(),
+            AggFunc::Std => "std".to_string(),
+            AggFunc::Min => "min".to_string(),
+            AggFunc::Max => "max".to_string(),
+            AggFunc::Sum => "sum".to_string(),
+            AggFunc::Quantile(q) => {
+                // Rounded so 0.07 reads `p7`, not `p7.000000000000001`.
+                let percent = format!("{:.6}", q * 100.0);
+                format!("p{}", percent.trim_end_matches('0').trim_end_matches('.'))
+            }
+        };
+        format!("{}_{}", self.column, suffix)
+    }
+}
+
+/// Rows of a table partitioned by the values of some key columns; see
+/// [`Table::group_by`].
+#[derive(Debug, Clone)]
+pub struct GroupBy<'a> {
+    table: &'a Table,
+    keys: Vec<String>,
+    /// Row indices per group, groups sorted by key.
+    groups: Vec<Vec<usize>>,
+}
+
+impl Table {
+    /// Groups rows by the values of `keys`. Missing key values form their
+    /// own group, sorted last, and every NaN key shares one group.
+    pub fn group_by(&self, keys: &[&str]) -> Result<GroupBy<'_>> {
+        let columns = keys
+            .iter()
+            .map(|&key| self.column(key))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut index: HashMap<Vec<ValueKey>, usize> = HashMap::new();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for row in 0..self.n_rows() {
+            let key = columns
+                .iter()
+                .map(|column| ValueKey(column.get(row)))
+                .collect();
+            let group = *index.entry(key).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(row);
+        }
+        groups.sort_by(|a, b| {
+            columns
+                .iter()
+                .map(|column| column.get(a[0]).total_cmp(&column.get(b[0])))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        Ok(GroupBy {
+            table: self,
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+            groups,
+        })
+    }
+}
+
+impl GroupBy<'_> {
+    pub fn n_groups(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Row indices of each group, in key order.
+    pub fn indices(&self) -> &[Vec<usize>] {
+        &self.groups
+    }
+
+    /// One row per group: the key columns followed by one column per
+    /// aggregation. `Count` and `Size` are `Int`; the rest are `Float`
+    /// and missing when undefined (e.g. the std of a single value).
+    pub fn agg(&self, aggs: &[Agg]) -> Result<Table> {
+        let firsts: Vec<usize> = self.groups.iter().map(|rows| rows[0]).collect();
+        let mut table = Table::new();
+        for key in &self.keys {
+            table.add_column(key.as_str(), self.table.column(key)?.take(&firsts))?;
+        }
+        for agg in aggs {
+            table.add_column(agg.name(), aggregate(self.table, &self.groups, agg)?)?;
+        }
+        Ok(table)
+    }
+}
+
+/// `agg` over each set of rows in `groups`. Shared by [`GroupBy::agg`]
+/// and the pivot tables, which aggregate cells and margins alike.
+pub(crate) fn aggregate(table: &Table, groups: &[Vec<usize>], agg: &Agg) -> Result<Column> {
+    if agg.func == AggFunc::Size {
+        let sizes = groups.iter().map(|rows| Some(rows.len() as i64));
+        return Ok(Column::Int(sizes.collect()));
+    }
+    if let AggFunc::Quantile(q) = agg.func {
+        if !(0.0..=1.0).contains(&q) {
+            bail!("quantile must be within 0..=1, got {}", q);
+        }
+    }
+    let cells = table.column(&agg.column)?.to_f64()?;
+    let values = groups.iter().map(|rows| {
+        let mut values: Vec<f64> = rows
+            .iter()
+            .filter_map(|&row| cells[row])
+            .filter(|v| !v.is_nan())
+            .collect();
+        values.sort_by(f64::total_cmp);
+        values
+    });
+
+    if agg.func == AggFunc::Count {
+        return Ok(Column::Int(values.map(|v| Some(v.len() as i64)).collect()));
+    }
+    Ok(Column::Float(
+        values.map(|values| reduce(agg.func, &values)).collect(),
+    ))
+}
+
+/// `values` are sorted and free of NaN.
+fn reduce(func: AggFunc, values: &[f64]) -> Option<f64> {
+    match func {
+        AggFunc::Size | AggFunc::Count => Some(values.len() as f64),
+        AggFunc::Mean => calc_mean(values).ok(),
+        AggFunc::Median => quantile_sorted(values, 0.5),
+        AggFunc::Std => calc_std(values).ok(),
+        AggFunc::Min => values.first().copied(),
+        AggFunc::Max => values.last().copied(),
+        AggFunc::Sum => Some(values.iter().copied().sum::<NeumaierSum>().value()),
+        AggFunc::Quantile(q) => quantile_sorted(values, q),
+    }
+}
@@ -0,0 +1,201 @@
+//! Hash joins between [`Table`]s, for enriching salary records with side
+//! tables such as countries keyed by `company_location` or cost of living
+//! keyed by `employee_residence` and `work_year`.
+//!
+//! The right table is hashed on its key columns and the left table probed
+//! row by row, so output rows follow the left table's order. A row with a
+//! missing or NaN key value never matches, as in SQL.
+
+use crate::table::{Column, Table, Value, ValueKey};
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+
+/// Which rows a join keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JoinKind {
+    /// Only left rows with a match.
+    #[default]
+    Inner,
+    /// Every left row; right columns are missing where nothing matched.
+    Left,
+    /// Only left rows without a match, with the left columns alone. Handy
+    /// for finding records a side table does not cover.
+    Anti,
+}
+
+/// What to do when several right rows share a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateKeys {
+    /// One output row per matching right row, as in SQL.
+    #[default]
+    All,
+    /// Only the first matching right row.
+    First,
+    /// Fail, naming the key and the right rows that share it.
+    Error,
+}
+
+/// Knobs for [`Table::join_with`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct JoinOptions {
+    pub kind: JoinKind,
+    pub duplicates: DuplicateKeys,
+    /// Appended to right column names that clash with left ones.
+    pub suffix: String,
+}
+
+impl Default for JoinOptions {
+    fn default() -> Self {
+        JoinOptions {
+            kind: JoinKind::Inner,
+            duplicates: DuplicateKeys::All,
+            suffix: "_right".to_string(),
+        }
+    }
+}
+
+impl JoinOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn kind(mut self, kind: JoinKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn duplicates(mut self, duplicates: DuplicateKeys) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    pub fn suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffix = suffix.into();
+        self
+    }
+}
+
+impl Table {
+    /// Joins `right` on key columns named the same in both tables, with
+    /// the default [`JoinOptions`] otherwise.
+    pub fn join(&self, right: &Table, on: &[&str], kind: JoinKind) -> Result<Table> {
+        self.join_with(right, on, on, &JoinOptions::new().kind(kind))
+    }
+
+    /// Joins `right` where `left_on` of this table equals `right_on` of
+    /// `right`, pairwise. Key columns must have the same type.
+    ///
+    /// The output has every left column followed by the right columns
+    /// other than `right_on`; clashing right names get `options.suffix`.
+    pub fn join_with(
+        &self,
+        right: &Table,
+        left_on: &[&str],
+        right_on: &[&str],
+        options: &JoinOptions,
+    ) -> Result<Table> {
+        if left_on.is_empty() || left_on.len() != right_on.len() {
+            bail!(
+                "join needs the same number of left and right keys, got {} and {}",
+                left_on.len(),
+                right_on.len()
+            );
+        }
+        let left_keys = key_columns(self, left_on)?;
+        let right_keys = key_columns(right, right_on)?;
+        for ((l, lc), (r, rc)) in left_on
+            .iter()
+            .zip(&left_keys)
+            .zip(right_on.iter().zip(&right_keys))
+        {
+            if lc.dtype() != rc.dtype() {
+                bail!(
+                    "join key '{}' is {} but '{}' is {}",
+                    l,
+                    lc.dtype(),
+                    r,
+                    rc.dtype()
+                );
+            }
+        }
+
+        let mut index: HashMap<Vec<ValueKey>, Vec<usize>> = HashMap::new();
+        for row in 0..right.n_rows() {
+            let Some(key) = row_key(&right_keys, row) else {
+                continue;
+            };
+            if options.duplicates == DuplicateKeys::Error {
+                if let Some(rows) = index.get(&key) {
+                    bail!(
+                        "duplicate join key ({}) in right table at rows {} and {}",
+                        key.iter()
+                            .map(|cell| cell.0.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        rows[0],
+                        row
+                    );
+                }
+            }
+            index.entry(key).or_default().push(row);
+        }
+
+        let mut left_rows = Vec::new();
+        let mut right_rows = Vec::new();
+        for row in 0..self.n_rows() {
+            let matches = row_key(&left_keys, row).and_then(|key| index.get(&key));
+            match (options.kind, matches) {
+                (JoinKind::Anti, None) => left_rows.push(row),
+                (JoinKind::Anti, Some(_)) | (JoinKind::Inner, None) => {}
+                (JoinKind::Left, None) => {
+                    left_rows.push(row);
+                    right_rows.push(None);
+                }
+                (_, Some(matches)) => {
+                    let matches = match options.duplicates {
+                        DuplicateKeys::First => &matches[..1],
+                        _ => &matches[..],
+                    };
+                    for &matched in matches {
+                        left_rows.push(row);
+                        right_rows.push(Some(matched));
+                    }
+                }
+            }
+        }
+
+        let mut table = self.take(&left_rows);
+        if options.kind == JoinKind::Anti {
+            return Ok(table);
+        }
+        for (name, column) in right.columns() {
+            if right_on.contains(&name) {
+                continue;
+            }
+            let name = if self.column_names().iter().any(|n| n == name) {
+                format!("{}{}", name, options.suffix)
+            } else {
+                name.to_string()
+            };
+            table.add_column(name, column.take_opt(&right_rows))?;
+        }
+        Ok(table)
+    }
+}
+
+fn key_columns<'a>(table: &'a Table, names: &[&str]) -> Result<Vec<&'a Column>> {
+    names.iter().map(|&name| table.column(name)).collect()
+}
+
+/// The key of `row`, or `None` if any key cell is missing or NaN, which
+/// never match anything.
+fn row_key(columns: &[&Column], row: usize) -> Option<Vec<ValueKey>> {
+    columns
+        .iter()
+        .map(|column| match column.get(row) {
+            Value::Null => None,
+            Value::Float(v) if v.is_nan() => None,
+            value => Some(ValueKey(value)),
+        })
+        .collect()
+}
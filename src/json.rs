@@ -0,0 +1,117 @@
+//! JSON and JSON Lines (NDJSON) input and output.
+//!
+//! Records read from JSON use the same field names and category codes as
+//! the CSV header, with `null` for missing values. The writers take any
+//! `Serialize` value, so records, [`crate::table::Table`]s, computed
+//! results such as [`crate::descriptive::Stat`] and
+//! [`crate::descriptive::RunningStats`], and inferred
+//! [`crate::schema::Schema`]s all go through them.
+
+use crate::dataset::{collect_with, LoadMode, Loaded, RowError, SalaryRecord};
+use crate::source::DataSource;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Lines, Read, Write};
+
+/// Parses a JSON array of records from any reader.
+pub fn load_json<R: Read>(rdr: R) -> Result<Vec<SalaryRecord>> {
+    let records =
+        serde_json::from_reader(BufReader::new(rdr)).context("failed to parse JSON records")?;
+    Ok(records)
+}
+
+/// Reads and parses the JSON array behind `source`.
+pub fn load_json_source(source: DataSource) -> Result<Vec<SalaryRecord>> {
+    load_json(source.open()?)
+}
+
+/// Parses JSON Lines / NDJSON, one record object per line, from any reader.
+pub fn load_json_lines<R: Read>(rdr: R) -> Result<Vec<SalaryRecord>> {
+    stream_json_lines(rdr).collect()
+}
+
+/// Reads and parses the JSON Lines behind `source`.
+pub fn load_json_lines_source(source: DataSource) -> Result<Vec<SalaryRecord>> {
+    load_json_lines(source.open()?)
+}
+
+/// [`load_json_lines`] with a choice of how to handle bad lines.
+pub fn load_json_lines_with<R: Read>(rdr: R, mode: LoadMode) -> Result<Loaded> {
+    collect_with(stream_json_lines(rdr), mode)
+}
+
+/// Lazily parses JSON Lines from `rdr`, one record at a time.
+///
+/// Blank lines are skipped. Lines that fail to parse come out as
+/// [`RowError`]s with their 1-based line number and the iterator carries
+/// on with the next line.
+pub fn stream_json_lines<R: Read>(rdr: R) -> JsonLines<R> {
+    JsonLines {
+        lines: BufReader::new(rdr).lines(),
+        line: 0,
+    }
+}
+
+/// Iterator returned by [`stream_json_lines`].
+pub struct JsonLines<R> {
+    lines: Lines<BufReader<R>>,
+    line: u64,
+}
+
+impl<R: Read> Iterator for JsonLines<R> {
+    type Item = Result<SalaryRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let text = match self.lines.next()? {
+                Ok(text) => text,
+                Err(error) => return Some(Err(error.into())),
+            };
+            self.line += 1;
+            if text.trim().is_empty() {
+                continue;
+            }
+            return Some(serde_json::from_str(&text).map_err(|error| {
+                RowError {
+                    line: self.line,
+                    column: None,
+                    message: message(&error),
+                }
+                .into()
+            }));
+        }
+    }
+}
+
+/// serde_json's message without its " at line 1 column N" suffix, which
+/// is meaningless for a single line.
+fn message(error: &serde_json::Error) -> String {
+    let text = error.to_string();
+    match text.rfind(" at line ") {
+        Some(end) => text[..end].to_string(),
+        None => text,
+    }
+}
+
+/// Writes `value` as pretty-printed JSON followed by a newline.
+pub fn write_json<W: Write, T: Serialize + ?Sized>(mut writer: W, value: &T) -> Result<()> {
+    serde_json::to_writer_pretty(&mut writer, value)?;
+    writer.write_all(b"\n")?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes each item as one compact JSON object per line (JSON Lines).
+pub fn write_json_lines<W, T, I>(mut writer: W, items: I) -> Result<()>
+where
+    W: Write,
+    T: Serialize,
+    I: IntoIterator<Item = T>,
+{
+    for item in items {
+        serde_json::to_writer(&mut writer, &item)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
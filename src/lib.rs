@@ -0,0 +1,30 @@
+//! Small statistics toolkit built around the `ds_salaries.csv` dataset.
+//!
+//! The examples under `examples/` import everything from here instead of
+//! carrying their own copies of the loaders and helpers.
+
+pub mod categories;
+#[cfg(feature = "columnar")]
+pub mod columnar;
+pub mod currency;
+pub mod dataset;
+pub mod descriptive;
+pub mod distributions;
+pub mod expr;
+pub mod features;
+#[cfg(feature = "url")]
+pub mod fetch;
+pub mod group;
+pub mod join;
+pub mod json;
+pub mod pivot;
+pub mod plot;
+pub mod profile;
+pub mod sample;
+pub mod schema;
+pub mod sort;
+pub mod source;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod table;
+pub mod validation;
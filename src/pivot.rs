@@ -0,0 +1,194 @@
+//! Pivot tables and crosstabs, e.g. a `job_title` × `work_year` grid of
+//! median salaries or a `remote_ratio` × `company_size` count table.
+//!
+//! The result is an ordinary [`Table`]: a `str` column of row keys, then
+//! one column per distinct value of the column key, both sorted as in
+//! [`Table::group_by`]. Print it with `{}` (or `{:.2}`) and export it with
+//! [`Table::write_csv`]. Rows with a missing row or column key are left
+//! out, as pandas does.
+
+use crate::group::{aggregate, Agg, AggFunc};
+use crate::table::{Column, Table};
+use anyhow::{bail, Result};
+
+/// Turns a pivot of counts or sums into shares, in `0..=1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Normalize {
+    #[default]
+    None,
+    /// Each cell over its row total.
+    Rows,
+    /// Each cell over its column total.
+    Columns,
+    /// Each cell over the grand total.
+    All,
+}
+
+/// Knobs for [`Table::pivot_with`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PivotOptions {
+    /// Adds a total row and column, aggregated over the underlying rows
+    /// (so a median margin is the median of the whole row, not of cells).
+    pub margins: bool,
+    /// Label of the total row and column.
+    pub margins_name: String,
+    pub normalize: Normalize,
+}
+
+impl Default for PivotOptions {
+    fn default() -> Self {
+        PivotOptions {
+            margins: false,
+            margins_name: "All".to_string(),
+            normalize: Normalize::None,
+        }
+    }
+}
+
+impl PivotOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn margins(mut self, margins: bool) -> Self {
+        self.margins = margins;
+        self
+    }
+
+    pub fn margins_name(mut self, name: impl Into<String>) -> Self {
+        self.margins_name = name.into();
+        self
+    }
+
+    pub fn normalize(mut self, normalize: Normalize) -> Self {
+        self.normalize = normalize;
+        self
+    }
+}
+
+impl Table {
+    /// `agg` for every pair of `index` and `columns` values, with the
+    /// default [`PivotOptions`].
+    pub fn pivot(&self, index: &str, columns: &str, agg: &Agg) -> Result<Table> {
+        self.pivot_with(index, columns, agg, &PivotOptions::default())
+    }
+
+    /// Row counts for every pair of `index` and `columns` values.
+    pub fn crosstab(&self, index: &str, columns: &str) -> Result<Table> {
+        self.pivot(index, columns, &Agg::size())
+    }
+
+    /// `agg` for every pair of `index` and `columns` values.
+    ///
+    /// Empty cells are 0 for `Size`, `Count` and `Sum` and missing for
+    /// the rest. Normalizing needs one of those three aggregations.
+    pub fn pivot_with(
+        &self,
+        index: &str,
+        columns: &str,
+        agg: &Agg,
+        options: &PivotOptions,
+    ) -> Result<Table> {
+        let additive = matches!(agg.func, AggFunc::Size | AggFunc::Count | AggFunc::Sum);
+        if options.normalize != Normalize::None && !additive {
+            bail!("normalizing needs a size, count or sum aggregation");
+        }
+
+        let row_keys = self.column(index)?;
+        let col_keys = self.column(columns)?;
+        let row_groups = keyed_groups(self, index)?;
+        let col_groups = keyed_groups(self, columns)?;
+        let mut col_of = vec![None; self.n_rows()];
+        for (c, rows) in col_groups.iter().enumerate() {
+            for &row in rows {
+                col_of[row] = Some(c);
+            }
+        }
+
+        // Cells row-major, then the row totals, the column totals and the
+        // grand total, aggregated in one pass.
+        let (n_rows, n_cols) = (row_groups.len(), col_groups.len());
+        let mut groups = vec![Vec::new(); n_rows * n_cols];
+        for (r, rows) in row_groups.iter().enumerate() {
+            for &row in rows {
+                if let Some(c) = col_of[row] {
+                    groups[r * n_cols + c].push(row);
+                }
+            }
+        }
+        let row_totals: Vec<Vec<usize>> = (0..n_rows)
+            .map(|r| groups[r * n_cols..(r + 1) * n_cols].concat())
+            .collect();
+        let col_totals: Vec<Vec<usize>> = (0..n_cols)
+            .map(|c| {
+                (0..n_rows)
+                    .flat_map(|r| groups[r * n_cols + c].clone())
+                    .collect()
+            })
+            .collect();
+        let total = row_totals.concat();
+        groups.extend(row_totals);
+        groups.extend(col_totals);
+        groups.push(total);
+
+        let values = aggregate(self, &groups, agg)?.to_f64()?;
+        let cell = |r: usize, c: usize| -> Option<f64> {
+            match (r < n_rows, c < n_cols) {
+                (true, true) => values[r * n_cols + c],
+                (true, false) => values[n_rows * n_cols + r],
+                (false, true) => values[n_rows * n_cols + n_rows + c],
+                (false, false) => values[n_rows * n_cols + n_rows + n_cols],
+            }
+        };
+        let value = |r: usize, c: usize| -> Option<f64> {
+            let denominator = match options.normalize {
+                Normalize::None => return cell(r, c),
+                Normalize::Rows => cell(r, n_cols),
+                Normalize::Columns => cell(n_rows, c),
+                Normalize::All => cell(n_rows, n_cols),
+            };
+            match (cell(r, c), denominator) {
+                (Some(v), Some(d)) if d != 0.0 => Some(v / d),
+                _ => None,
+            }
+        };
+
+        let shown_rows = n_rows + usize::from(options.margins);
+        let integer = matches!(agg.func, AggFunc::Size | AggFunc::Count)
+            && options.normalize == Normalize::None;
+
+        let mut labels: Vec<Option<String>> = row_groups
+            .iter()
+            .map(|rows| Some(row_keys.get(rows[0]).to_string()))
+            .collect();
+        let mut names: Vec<String> = col_groups
+            .iter()
+            .map(|rows| col_keys.get(rows[0]).to_string())
+            .collect();
+        if options.margins {
+            labels.push(Some(options.margins_name.clone()));
+            names.push(options.margins_name.clone());
+        }
+
+        let mut table = Table::new();
+        table.add_column(index, Column::Str(labels))?;
+        for (c, name) in names.into_iter().enumerate() {
+            let cells: Vec<Option<f64>> = (0..shown_rows).map(|r| value(r, c)).collect();
+            let column = if integer {
+                Column::Int(cells.into_iter().map(|v| v.map(|v| v as i64)).collect())
+            } else {
+                Column::Float(cells)
+            };
+            table.add_column(name, column)?;
+        }
+        Ok(table)
+    }
+}
+
+/// Row indices per value of `key`, sorted, without the missing-key group.
+fn keyed_groups(table: &Table, key: &str) -> Result<Vec<Vec<usize>>> {
+    let column = table.column(key)?;
+    let mut groups = table.group_by(&[key])?.indices().to_vec();
+    groups.retain(|rows| !column.is_null(rows[0]));
+    Ok(groups)
+}
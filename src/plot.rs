@@ -0,0 +1,14 @@
+use plotly::{common::Title, Histogram, Layout, Plot};
+
+/// Writes a histogram of `col` with `bins` bins to the HTML file at `path`.
+pub fn plot_histogram(col: &[f32], bins: usize, title: &str, path: &str) {
+    let trace = Histogram::new(col.into())
+        .n_bins_x(bins)
+        .name("Salary Distribution");
+
+    let layout = Layout::new().title(Title::new(title));
+    let mut plot = Plot::new();
+    plot.add_trace(trace);
+    plot.set_layout(layout);
+    plot.write_html(path);
+}
@@ -0,0 +1,269 @@
+//! One-call summary of every column, in the spirit of pandas `describe()`.
+//!
+//! `Int` and `Float` columns are profiled as numeric; everything else is
+//! profiled as categorical, with values compared as text. A [`Profile`]
+//! prints as two text tables and serializes to JSON.
+
+use crate::dataset::SalaryRecord;
+use crate::descriptive::{calc_mean, calc_std, quantile_sorted};
+use crate::table::{write_grid, Column, DataType, Table, Value};
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Knobs for [`profile_table_with`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileOptions {
+    /// Number of most frequent values kept per categorical column.
+    pub top_k: usize,
+}
+
+impl Default for ProfileOptions {
+    fn default() -> Self {
+        ProfileOptions { top_k: 5 }
+    }
+}
+
+impl ProfileOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn top_k(mut self, top_k: usize) -> Self {
+        self.top_k = top_k;
+        self
+    }
+}
+
+/// Summary of a numeric column. Statistics that need more values than
+/// the column has are `None`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NumericProfile {
+    /// Non-missing values.
+    pub count: usize,
+    pub missing: usize,
+    pub mean: Option<f64>,
+    /// Sample standard deviation (n - 1); needs two values.
+    pub std: Option<f64>,
+    pub min: Option<f64>,
+    pub q25: Option<f64>,
+    pub median: Option<f64>,
+    pub q75: Option<f64>,
+    pub max: Option<f64>,
+    /// Adjusted Fisher-Pearson skewness, as pandas reports it; needs three
+    /// values that are not all equal.
+    pub skew: Option<f64>,
+    pub zeros: usize,
+}
+
+/// Summary of a categorical column.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CategoricalProfile {
+    /// Non-missing values.
+    pub count: usize,
+    pub missing: usize,
+    pub distinct: usize,
+    /// Most frequent values with their counts, most frequent first; ties
+    /// are broken alphabetically.
+    pub top: Vec<(String, usize)>,
+    /// Shannon entropy of the value distribution, in bits.
+    pub entropy: f64,
+}
+
+/// Per-kind statistics of a [`ColumnProfile`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ColumnStats {
+    Numeric(NumericProfile),
+    Categorical(CategoricalProfile),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColumnProfile {
+    pub name: String,
+    /// Storage type, e.g. `float` or `str`.
+    pub dtype: String,
+    #[serde(flatten)]
+    pub stats: ColumnStats,
+}
+
+/// Result of [`profile`] and [`profile_table`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Profile {
+    pub rows: usize,
+    pub columns: Vec<ColumnProfile>,
+}
+
+impl Profile {
+    /// The profile of the column `name`, if there is one.
+    pub fn column(&self, name: &str) -> Option<&ColumnProfile> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+}
+
+/// Profiles every field of the salary records.
+pub fn profile(dataset: &[SalaryRecord]) -> Result<Profile> {
+    profile_table(&Table::from_records(dataset))
+}
+
+/// Profiles every column of `table` with the default [`ProfileOptions`].
+pub fn profile_table(table: &Table) -> Result<Profile> {
+    profile_table_with(table, &ProfileOptions::default())
+}
+
+/// Profiles every column of `table`.
+pub fn profile_table_with(table: &Table, options: &ProfileOptions) -> Result<Profile> {
+    let columns = table
+        .columns()
+        .map(|(name, column)| {
+            let stats = match column.dtype() {
+                DataType::Int | DataType::Float => {
+                    ColumnStats::Numeric(numeric_profile(column.to_f64()?))
+                }
+                _ => ColumnStats::Categorical(categorical_profile(column, options.top_k)),
+            };
+            Ok(ColumnProfile {
+                name: name.to_string(),
+                dtype: column.dtype().to_string(),
+                stats,
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok(Profile {
+        rows: table.n_rows(),
+        columns,
+    })
+}
+
+fn numeric_profile(cells: Vec<Option<f64>>) -> NumericProfile {
+    let missing = cells.iter().filter(|c| c.is_none_or(f64::is_nan)).count();
+    let mut values: Vec<f64> = cells
+        .into_iter()
+        .flatten()
+        .filter(|v| !v.is_nan())
+        .collect();
+    values.sort_by(f64::total_cmp);
+
+    let n = values.len();
+    let mean = calc_mean(&values).ok();
+    let moment = |k: i32| {
+        let mean = mean.unwrap_or(0.0);
+        values.iter().map(|v| (v - mean).powi(k)).sum::<f64>() / n as f64
+    };
+    let std = calc_std(&values).ok();
+    let skew = if n > 2 && moment(2) > 0.0 {
+        let n = n as f64;
+        let g1 = moment(3) / moment(2).powf(1.5);
+        Some(g1 * (n * (n - 1.0)).sqrt() / (n - 2.0))
+    } else {
+        None
+    };
+
+    NumericProfile {
+        count: n,
+        missing,
+        mean,
+        std,
+        min: values.first().copied(),
+        q25: quantile_sorted(&values, 0.25),
+        median: quantile_sorted(&values, 0.5),
+        q75: quantile_sorted(&values, 0.75),
+        max: values.last().copied(),
+        skew,
+        zeros: values.iter().filter(|&&v| v == 0.0).count(),
+    }
+}
+
+fn categorical_profile(column: &Column, top_k: usize) -> CategoricalProfile {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let mut missing = 0;
+    for i in 0..column.len() {
+        match column.get(i) {
+            Value::Null => missing += 1,
+            value => *counts.entry(value.to_string()).or_default() += 1,
+        }
+    }
+    let count = column.len() - missing;
+    let entropy = counts
+        .values()
+        .map(|&c| {
+            let p = c as f64 / count as f64;
+            -p * p.log2()
+        })
+        .sum::<f64>()
+        // An empty or single-valued column sums to -0.0.
+        .max(0.0);
+
+    let distinct = counts.len();
+    let mut top: Vec<(String, usize)> = counts.into_iter().collect();
+    top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    top.truncate(top_k);
+
+    CategoricalProfile {
+        count,
+        missing,
+        distinct,
+        top,
+        entropy,
+    }
+}
+
+/// Numeric columns first, then categorical ones, each as a text table with
+/// one row per column.
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut numeric = Vec::new();
+        let mut categorical = Vec::new();
+        for column in &self.columns {
+            match &column.stats {
+                ColumnStats::Numeric(p) => numeric.push(vec![
+                    column.name.clone(),
+                    p.count.to_string(),
+                    p.missing.to_string(),
+                    number(p.mean),
+                    number(p.std),
+                    number(p.min),
+                    number(p.q25),
+                    number(p.median),
+                    number(p.q75),
+                    number(p.max),
+                    number(p.skew),
+                    p.zeros.to_string(),
+                ]),
+                ColumnStats::Categorical(p) => categorical.push(vec![
+                    column.name.clone(),
+                    p.count.to_string(),
+                    p.missing.to_string(),
+                    p.distinct.to_string(),
+                    format!("{:.3}", p.entropy),
+                    p.top
+                        .iter()
+                        .map(|(value, n)| format!("{} ({})", value, n))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ]),
+            }
+        }
+
+        writeln!(f, "{} rows", self.rows)?;
+        if !numeric.is_empty() {
+            writeln!(f)?;
+            let header = [
+                "column", "count", "missing", "mean", "std", "min", "25%", "50%", "75%", "max",
+                "skew", "zeros",
+            ];
+            write_grid(f, &header, &numeric, false)?;
+        }
+        if !categorical.is_empty() {
+            writeln!(f)?;
+            let header = ["column", "count", "missing", "distinct", "entropy", "top"];
+            write_grid(f, &header, &categorical, true)?;
+        }
+        Ok(())
+    }
+}
+
+fn number(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("{:.3}", v))
+}
@@ -0,0 +1,162 @@
+//! Seedable random sampling and train/test splits.
+//!
+//! Every function takes a `seed`, so the same call on the same data picks
+//! the same rows (for a given `rand` version). Picked rows keep their
+//! original order. Stratified functions sample each category separately,
+//! so category proportions survive: `round(fraction * size)` rows are
+//! taken from every stratum.
+
+use crate::dataset::SalaryRecord;
+use crate::table::Table;
+use anyhow::{bail, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeMap;
+
+/// Rows of a [`train_test_split`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Split<T> {
+    pub train: Vec<T>,
+    pub test: Vec<T>,
+}
+
+/// `k` distinct indices out of `0..n`, sorted.
+pub fn sample_indices(n: usize, k: usize, seed: u64) -> Result<Vec<usize>> {
+    if k > n {
+        bail!("cannot sample {} of {} rows", k, n);
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut indices = rand::seq::index::sample(&mut rng, n, k).into_vec();
+    indices.sort_unstable();
+    Ok(indices)
+}
+
+/// A `fraction` of the indices of every stratum, where `keys[i]` is the
+/// stratum of row `i`. Sorted.
+pub fn stratified_indices<K: Ord>(keys: &[K], fraction: f64, seed: u64) -> Result<Vec<usize>> {
+    check_fraction(fraction)?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut indices = Vec::new();
+    for rows in strata(keys) {
+        indices.extend(pick(&mut rng, &rows, fraction));
+    }
+    indices.sort_unstable();
+    Ok(indices)
+}
+
+/// `k` records picked uniformly at random without replacement.
+pub fn sample_records(dataset: &[SalaryRecord], k: usize, seed: u64) -> Result<Vec<SalaryRecord>> {
+    let indices = sample_indices(dataset.len(), k, seed)?;
+    Ok(indices.into_iter().map(|i| dataset[i].clone()).collect())
+}
+
+/// A `fraction` of the records of every stratum, e.g. per experience
+/// level with `|r| r.experience_level`.
+pub fn stratified_sample<K: Ord>(
+    dataset: &[SalaryRecord],
+    key: impl Fn(&SalaryRecord) -> K,
+    fraction: f64,
+    seed: u64,
+) -> Result<Vec<SalaryRecord>> {
+    let keys: Vec<K> = dataset.iter().map(key).collect();
+    let indices = stratified_indices(&keys, fraction, seed)?;
+    Ok(indices.into_iter().map(|i| dataset[i].clone()).collect())
+}
+
+/// Up to `k` items picked uniformly at random from a stream of unknown
+/// length, holding only `k` items in memory (reservoir sampling). Works
+/// with [`stream_records`](crate::dataset::stream_records); the first
+/// error aborts the sample.
+pub fn reservoir_sample<T, I>(items: I, k: usize, seed: u64) -> Result<Vec<T>>
+where
+    I: IntoIterator<Item = Result<T>>,
+{
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut reservoir: Vec<(usize, T)> = Vec::with_capacity(k);
+    for (i, item) in items.into_iter().enumerate() {
+        let item = item?;
+        if reservoir.len() < k {
+            reservoir.push((i, item));
+        } else {
+            let j = rng.gen_range(0..=i);
+            if j < k {
+                reservoir[j] = (i, item);
+            }
+        }
+    }
+    reservoir.sort_unstable_by_key(|&(i, _)| i);
+    Ok(reservoir.into_iter().map(|(_, item)| item).collect())
+}
+
+/// Holds out a random `test_fraction` of the records for testing.
+pub fn train_test_split(
+    dataset: &[SalaryRecord],
+    test_fraction: f64,
+    seed: u64,
+) -> Result<Split<SalaryRecord>> {
+    stratified_train_test_split(dataset, |_| (), test_fraction, seed)
+}
+
+/// [`train_test_split`] that holds out `test_fraction` of every stratum,
+/// so train and test keep the category proportions of `dataset`.
+pub fn stratified_train_test_split<K: Ord>(
+    dataset: &[SalaryRecord],
+    key: impl Fn(&SalaryRecord) -> K,
+    test_fraction: f64,
+    seed: u64,
+) -> Result<Split<SalaryRecord>> {
+    let keys: Vec<K> = dataset.iter().map(key).collect();
+    let mut test = vec![false; dataset.len()];
+    for i in stratified_indices(&keys, test_fraction, seed)? {
+        test[i] = true;
+    }
+    let (test, train): (Vec<_>, Vec<_>) = dataset.iter().zip(test).partition(|(_, hit)| *hit);
+    Ok(Split {
+        train: train.into_iter().map(|(r, _)| r.clone()).collect(),
+        test: test.into_iter().map(|(r, _)| r.clone()).collect(),
+    })
+}
+
+impl Table {
+    /// `k` rows picked uniformly at random without replacement.
+    pub fn sample(&self, k: usize, seed: u64) -> Result<Table> {
+        Ok(self.take(&sample_indices(self.n_rows(), k, seed)?))
+    }
+
+    /// A `fraction` of the rows of every group of `column`; missing cells
+    /// form a group of their own.
+    pub fn stratified_sample(&self, column: &str, fraction: f64, seed: u64) -> Result<Table> {
+        check_fraction(fraction)?;
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut indices = Vec::new();
+        for rows in self.group_by(&[column])?.indices() {
+            indices.extend(pick(&mut rng, rows, fraction));
+        }
+        indices.sort_unstable();
+        Ok(self.take(&indices))
+    }
+}
+
+fn check_fraction(fraction: f64) -> Result<()> {
+    if !(0.0..=1.0).contains(&fraction) {
+        bail!("fraction must be within 0..=1, got {}", fraction);
+    }
+    Ok(())
+}
+
+/// Row indices per distinct key, in key order.
+fn strata<K: Ord>(keys: &[K]) -> Vec<Vec<usize>> {
+    let mut groups: BTreeMap<&K, Vec<usize>> = BTreeMap::new();
+    for (i, key) in keys.iter().enumerate() {
+        groups.entry(key).or_default().push(i);
+    }
+    groups.into_values().collect()
+}
+
+fn pick(rng: &mut StdRng, rows: &[usize], fraction: f64) -> Vec<usize> {
+    let k = (fraction * rows.len() as f64).round() as usize;
+    rand::seq::index::sample(rng, rows.len(), k)
+        .into_iter()
+        .map(|i| rows[i])
+        .collect()
+}
@@ -0,0 +1,339 @@
+use crate::table::{Column, DataType, Table};
+use anyhow::{bail, Result};
+use chrono::NaiveDate;
+use csv::{ReaderBuilder, StringRecord};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::Read;
+
+/// Cells that count as missing, besides the empty string.
+const NULL_TOKENS: [&str; 5] = ["", "NA", "N/A", "null", "NULL"];
+
+/// Date layouts recognised during inference.
+const DATE_FORMATS: [&str; 2] = ["%Y-%m-%d", "%Y/%m/%d"];
+
+/// Share of sampled values that must parse as a narrower type before a
+/// text column is reported as ambiguous.
+const AMBIGUITY_THRESHOLD: f64 = 0.9;
+
+/// Semantic type of a column, as inferred from its values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnType {
+    Int,
+    Float,
+    Bool,
+    Date,
+    /// Text with few distinct values relative to the number of rows.
+    Categorical,
+    /// Free text.
+    Text,
+}
+
+impl ColumnType {
+    /// How values of this type are stored in a [`Table`].
+    pub fn storage(self) -> DataType {
+        match self {
+            ColumnType::Int => DataType::Int,
+            ColumnType::Float => DataType::Float,
+            ColumnType::Bool => DataType::Bool,
+            ColumnType::Date => DataType::Date,
+            ColumnType::Categorical | ColumnType::Text => DataType::Str,
+        }
+    }
+}
+
+impl fmt::Display for ColumnType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ColumnType::Int => "int",
+            ColumnType::Float => "float",
+            ColumnType::Bool => "bool",
+            ColumnType::Date => "date",
+            ColumnType::Categorical => "categorical",
+            ColumnType::Text => "text",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Inference result for one column.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnSchema {
+    pub name: String,
+    pub column_type: ColumnType,
+    /// Whether any sampled cell was missing.
+    pub nullable: bool,
+    /// Whether `column_type` came from [`InferOptions::override_type`].
+    pub overridden: bool,
+    /// Why the inferred type might be wrong, if there is reason to doubt it.
+    pub ambiguity: Option<String>,
+}
+
+/// Column types for a whole CSV, in header order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Schema {
+    pub columns: Vec<ColumnSchema>,
+}
+
+impl Schema {
+    pub fn column(&self, name: &str) -> Option<&ColumnSchema> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    /// Columns whose inferred type deserves a second look.
+    pub fn ambiguous(&self) -> impl Iterator<Item = &ColumnSchema> {
+        self.columns
+            .iter()
+            .filter(|column| column.ambiguity.is_some())
+    }
+
+    pub(crate) fn check_headers(&self, headers: &[String]) -> Result<()> {
+        let names: Vec<&str> = self.columns.iter().map(|c| c.name.as_str()).collect();
+        if names != headers {
+            bail!(
+                "CSV header {:?} does not match schema columns {:?}",
+                headers,
+                names
+            );
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .columns
+            .iter()
+            .map(|c| c.name.len())
+            .chain(["column".len()])
+            .max()
+            .unwrap_or(0);
+        writeln!(
+            f,
+            "{:<width$}  {:<11}  {:<8}  note",
+            "column", "type", "nullable"
+        )?;
+        for column in &self.columns {
+            let mut note = column.ambiguity.clone().unwrap_or_default();
+            if column.overridden {
+                note = format!("overridden {}", note).trim_end().to_string();
+            }
+            writeln!(
+                f,
+                "{:<width$}  {:<11}  {:<8}  {}",
+                column.name,
+                column.column_type.to_string(),
+                if column.nullable { "yes" } else { "no" },
+                note
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Knobs for [`infer_schema`] and [`Table::from_reader_with`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct InferOptions {
+    /// Number of data rows [`infer_schema`] reads per column. Readers that
+    /// hold every row anyway, like [`Table::from_reader_with`], inspect
+    /// them all.
+    pub sample_rows: usize,
+    /// Text columns whose distinct/non-missing ratio is at or below this
+    /// are categorical; above it they are free text.
+    pub categorical_ratio: f64,
+    /// Column types that skip inference entirely.
+    pub overrides: HashMap<String, ColumnType>,
+}
+
+impl Default for InferOptions {
+    fn default() -> Self {
+        InferOptions {
+            sample_rows: 1000,
+            categorical_ratio: 0.5,
+            overrides: HashMap::new(),
+        }
+    }
+}
+
+impl InferOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn sample_rows(mut self, rows: usize) -> Self {
+        self.sample_rows = rows;
+        self
+    }
+
+    pub fn categorical_ratio(mut self, ratio: f64) -> Self {
+        self.categorical_ratio = ratio;
+        self
+    }
+
+    /// Pins `column` to `column_type`, the way `SalaryRecord` pins
+    /// `remote_ratio` to a float.
+    pub fn override_type(mut self, column: impl Into<String>, column_type: ColumnType) -> Self {
+        self.overrides.insert(column.into(), column_type);
+        self
+    }
+}
+
+/// Infers a schema from the header and the first `options.sample_rows`
+/// rows of a CSV. The rest of the input is not read.
+pub fn infer_schema<R: Read>(rdr: R, options: &InferOptions) -> Result<Schema> {
+    let mut reader = ReaderBuilder::new().from_reader(rdr);
+    let headers: Vec<String> = reader.headers()?.iter().map(str::to_string).collect();
+    let rows = reader
+        .records()
+        .take(options.sample_rows)
+        .collect::<Result<Vec<_>, _>>()?;
+    infer_rows(&headers, &rows, options)
+}
+
+pub(crate) fn read_raw<R: Read>(rdr: R) -> Result<(Vec<String>, Vec<StringRecord>)> {
+    let mut reader = ReaderBuilder::new().from_reader(rdr);
+    let headers: Vec<String> = reader.headers()?.iter().map(str::to_string).collect();
+    let rows = reader.records().collect::<Result<Vec<_>, _>>()?;
+    Ok((headers, rows))
+}
+
+/// Infers a schema from every row given; callers cap the rows themselves.
+pub(crate) fn infer_rows(
+    headers: &[String],
+    rows: &[StringRecord],
+    options: &InferOptions,
+) -> Result<Schema> {
+    for name in options.overrides.keys() {
+        if !headers.contains(name) {
+            bail!("type override for unknown column '{}'", name);
+        }
+    }
+
+    let columns = headers
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let cells: Vec<&str> = rows.iter().map(|row| row[i].trim()).collect();
+            let mut column = infer_column(name, &cells, options.categorical_ratio);
+            if let Some(&column_type) = options.overrides.get(name) {
+                column.column_type = column_type;
+                column.overridden = true;
+            }
+            column
+        })
+        .collect();
+    Ok(Schema { columns })
+}
+
+fn infer_column(name: &str, cells: &[&str], categorical_ratio: f64) -> ColumnSchema {
+    let present: Vec<&str> = cells.iter().copied().filter(|c| !is_null(c)).collect();
+    let mut column = ColumnSchema {
+        name: name.to_string(),
+        column_type: ColumnType::Text,
+        nullable: present.len() < cells.len(),
+        overridden: false,
+        ambiguity: None,
+    };
+    if present.is_empty() {
+        column.ambiguity = Some("no non-missing values in sample".to_string());
+        return column;
+    }
+
+    let n = present.len();
+    let count = |parses: fn(&str) -> bool| present.iter().filter(|c| parses(c)).count();
+    let candidates = [
+        (ColumnType::Int, count(|c| c.parse::<i64>().is_ok())),
+        (ColumnType::Float, count(|c| c.parse::<f64>().is_ok())),
+        (ColumnType::Bool, count(|c| parse_bool(c).is_some())),
+        (ColumnType::Date, count(|c| parse_date(c).is_some())),
+    ];
+
+    if let Some(&(column_type, _)) = candidates.iter().find(|(_, hits)| *hits == n) {
+        column.column_type = column_type;
+        if column_type == ColumnType::Int && present.iter().all(|c| *c == "0" || *c == "1") {
+            column.ambiguity = Some("only 0/1 values; could be bool".to_string());
+        }
+        return column;
+    }
+
+    let distinct: HashSet<&str> = present.iter().copied().collect();
+    column.column_type = if distinct.len() as f64 / n as f64 <= categorical_ratio {
+        ColumnType::Categorical
+    } else {
+        ColumnType::Text
+    };
+    // Reversed so ties go to the narrowest type.
+    let (best, hits) = candidates
+        .iter()
+        .rev()
+        .max_by_key(|(_, hits)| *hits)
+        .copied()
+        .unwrap();
+    if hits as f64 / n as f64 >= AMBIGUITY_THRESHOLD {
+        column.ambiguity = Some(format!("{} of {} values parse as {}", hits, n, best));
+    }
+    column
+}
+
+pub(crate) fn build_table(schema: &Schema, rows: &[StringRecord]) -> Result<Table> {
+    let mut table = Table::new();
+    for (i, column) in schema.columns.iter().enumerate() {
+        let cells = rows.iter().map(|row| {
+            let cell = row[i].trim();
+            let line = row.position().map_or(0, |p| p.line());
+            (line, (!is_null(cell)).then_some(cell))
+        });
+        let parsed = match column.column_type.storage() {
+            DataType::Int => Column::Int(parse_cells(column, cells, |c| c.parse().ok())?),
+            DataType::Float => Column::Float(parse_cells(column, cells, |c| c.parse().ok())?),
+            DataType::Bool => Column::Bool(parse_cells(column, cells, parse_bool)?),
+            DataType::Date => Column::Date(parse_cells(column, cells, parse_date)?),
+            DataType::Str => Column::Str(cells.map(|(_, c)| c.map(str::to_string)).collect()),
+        };
+        table.add_column(column.name.clone(), parsed)?;
+    }
+    Ok(table)
+}
+
+fn parse_cells<'a, T>(
+    column: &ColumnSchema,
+    cells: impl Iterator<Item = (u64, Option<&'a str>)>,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Result<Vec<Option<T>>> {
+    cells
+        .map(|(line, cell)| match cell {
+            None => Ok(None),
+            Some(cell) => match parse(cell) {
+                Some(value) => Ok(Some(value)),
+                None => bail!(
+                    "line {}, column '{}': '{}' is not a valid {}",
+                    line,
+                    column.name,
+                    cell,
+                    column.column_type
+                ),
+            },
+        })
+        .collect()
+}
+
+fn is_null(cell: &str) -> bool {
+    NULL_TOKENS.contains(&cell)
+}
+
+fn parse_bool(cell: &str) -> Option<bool> {
+    match cell.to_ascii_lowercase().as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+pub(crate) fn parse_date(cell: &str) -> Option<NaiveDate> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(cell, format).ok())
+}
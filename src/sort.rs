@@ -0,0 +1,210 @@
+//! Multi-key sorting, top-k selection and ranking of [`Table`] rows.
+//!
+//! Missing cells and NaN sort together, first or last as the [`SortKey`]
+//! says, regardless of direction. All orderings are stable: rows that tie
+//! on every key keep their table order.
+
+use crate::table::{Column, Table, Value};
+use anyhow::Result;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Where missing values and NaN go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullOrder {
+    First,
+    #[default]
+    Last,
+}
+
+/// One column to sort by.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortKey {
+    pub column: String,
+    pub descending: bool,
+    pub nulls: NullOrder,
+}
+
+impl SortKey {
+    pub fn asc(column: impl Into<String>) -> Self {
+        SortKey {
+            column: column.into(),
+            descending: false,
+            nulls: NullOrder::Last,
+        }
+    }
+
+    pub fn desc(column: impl Into<String>) -> Self {
+        SortKey {
+            descending: true,
+            ..SortKey::asc(column)
+        }
+    }
+
+    pub fn nulls_first(mut self) -> Self {
+        self.nulls = NullOrder::First;
+        self
+    }
+
+    pub fn nulls_last(mut self) -> Self {
+        self.nulls = NullOrder::Last;
+        self
+    }
+
+    fn compare(&self, a: &Value, b: &Value) -> Ordering {
+        match (is_missing(a), is_missing(b)) {
+            (true, true) => Ordering::Equal,
+            (true, false) if self.nulls == NullOrder::First => Ordering::Less,
+            (true, false) => Ordering::Greater,
+            (false, true) if self.nulls == NullOrder::First => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) if self.descending => b.total_cmp(a),
+            (false, false) => a.total_cmp(b),
+        }
+    }
+}
+
+/// How [`Table::rank`] numbers tied values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RankMethod {
+    /// Ties share the lowest rank and the next value follows on:
+    /// 1, 2, 2, 3.
+    Dense,
+    /// Ties share the lowest rank and leave a gap: 1, 2, 2, 4.
+    Min,
+    /// [`RankMethod::Min`] divided by the number of ranked values, in
+    /// `(0, 1]`, like pandas `rank(method="min", pct=True)`.
+    Percent,
+}
+
+fn is_missing(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Float(v) => v.is_nan(),
+        _ => false,
+    }
+}
+
+/// The key columns of `table` materialized as values, one `Vec` per key.
+fn key_values(table: &Table, keys: &[SortKey]) -> Result<Vec<Vec<Value>>> {
+    keys.iter()
+        .map(|key| {
+            let column = table.column(&key.column)?;
+            Ok((0..column.len()).map(|i| column.get(i)).collect())
+        })
+        .collect()
+}
+
+fn compare_rows(keys: &[SortKey], values: &[Vec<Value>], a: usize, b: usize) -> Ordering {
+    keys.iter()
+        .zip(values)
+        .map(|(key, column)| key.compare(&column[a], &column[b]))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// A candidate row for [`Table::top_k`]. The heap keeps the worst kept
+/// row on top, so ties are broken by row index to stay stable.
+struct Candidate<'a> {
+    row: usize,
+    keys: &'a [SortKey],
+    values: &'a [Vec<Value>],
+}
+
+impl Ord for Candidate<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_rows(self.keys, self.values, self.row, other.row).then(self.row.cmp(&other.row))
+    }
+}
+
+impl PartialOrd for Candidate<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Candidate<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Candidate<'_> {}
+
+impl Table {
+    /// Row order that sorts the table by `keys`, earlier keys first.
+    pub fn sort_indices(&self, keys: &[SortKey]) -> Result<Vec<usize>> {
+        let values = key_values(self, keys)?;
+        let mut indices: Vec<usize> = (0..self.n_rows()).collect();
+        indices.sort_by(|&a, &b| compare_rows(keys, &values, a, b));
+        Ok(indices)
+    }
+
+    /// A copy sorted by `keys`; see [`Table::sort_indices`].
+    pub fn sort_by(&self, keys: &[SortKey]) -> Result<Table> {
+        Ok(self.take(&self.sort_indices(keys)?))
+    }
+
+    /// The first `k` rows in `keys` order, without sorting the whole
+    /// table: a bounded heap keeps the best `k` rows seen so far, so this
+    /// takes O(n log k) time.
+    pub fn top_k(&self, k: usize, keys: &[SortKey]) -> Result<Table> {
+        let values = key_values(self, keys)?;
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            for row in 0..self.n_rows() {
+                heap.push(Candidate {
+                    row,
+                    keys,
+                    values: &values,
+                });
+                if heap.len() > k {
+                    heap.pop();
+                }
+            }
+        }
+        let rows: Vec<usize> = heap.into_sorted_vec().iter().map(|c| c.row).collect();
+        Ok(self.take(&rows))
+    }
+
+    /// Rank of every row by `key`, computed over the whole table or, with
+    /// `within`, separately inside each group of those columns. Rank 1 is
+    /// the first row in `key` order. Missing values get no rank.
+    ///
+    /// `Dense` and `Min` ranks are `Int`; `Percent` ranks are `Float`.
+    pub fn rank(&self, key: &SortKey, method: RankMethod, within: &[&str]) -> Result<Column> {
+        let keys = std::slice::from_ref(key);
+        let values = key_values(self, keys)?;
+        let groups = if within.is_empty() {
+            vec![(0..self.n_rows()).collect()]
+        } else {
+            self.group_by(within)?.indices().to_vec()
+        };
+
+        let mut ranks: Vec<Option<f64>> = vec![None; self.n_rows()];
+        for mut rows in groups {
+            rows.retain(|&row| !is_missing(&values[0][row]));
+            rows.sort_by(|&a, &b| compare_rows(keys, &values, a, b));
+            let ranked = rows.len() as f64;
+            let mut dense = 0;
+            let mut min = 0;
+            for (i, &row) in rows.iter().enumerate() {
+                let tied = i > 0 && compare_rows(keys, &values, rows[i - 1], row).is_eq();
+                if !tied {
+                    dense += 1;
+                    min = i + 1;
+                }
+                ranks[row] = Some(match method {
+                    RankMethod::Dense => dense as f64,
+                    RankMethod::Min => min as f64,
+                    RankMethod::Percent => min as f64 / ranked,
+                });
+            }
+        }
+
+        Ok(match method {
+            RankMethod::Percent => Column::Float(ranks),
+            _ => Column::Int(ranks.into_iter().map(|r| r.map(|r| r as i64)).collect()),
+        })
+    }
+}
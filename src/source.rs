@@ -0,0 +1,253 @@
+use anyhow::{bail, Context, Result};
+use flate2::read::{DeflateDecoder, MultiGzDecoder};
+use flate2::CrcReader;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use zip::CompressionMethod;
+
+/// Where a dataset's bytes come from.
+///
+/// Every variant is opened into a plain `Read` so the CSV parsing in
+/// [`crate::dataset`] stays the same regardless of origin. Compressed
+/// inputs are decompressed on the way; see [`Compression`].
+pub enum DataSource {
+    /// A file on the local filesystem.
+    Path(PathBuf),
+    /// The process's standard input.
+    Stdin,
+    /// Any caller-provided reader (an in-memory buffer, a socket, ...).
+    Reader(Box<dyn Read>),
+    /// A remote file fetched over HTTP(S), streamed unless `options` pin
+    /// a checksum or set a cache; see [`crate::fetch::fetch_reader`].
+    /// Requires the `url` feature.
+    #[cfg(feature = "url")]
+    Url {
+        url: String,
+        options: crate::fetch::FetchOptions,
+    },
+    /// One named file inside a zip archive read from another source.
+    ZipMember {
+        archive: Box<DataSource>,
+        member: String,
+    },
+}
+
+/// Bytes [`Compression::from_magic`] needs to rule every format out.
+const MAGIC_LEN: usize = 4;
+
+/// Compression formats recognised by [`DataSource::open`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Plain,
+    Gzip,
+    Zstd,
+    Zip,
+}
+
+impl Compression {
+    /// Detects the format from the first bytes of the input.
+    pub fn from_magic(bytes: &[u8]) -> Self {
+        match bytes {
+            [0x1f, 0x8b, ..] => Compression::Gzip,
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Compression::Zstd,
+            [b'P', b'K', 0x03, 0x04, ..] => Compression::Zip,
+            _ => Compression::Plain,
+        }
+    }
+
+    /// Guesses the format from a file name such as `salaries.csv.gz`.
+    pub fn from_extension(name: &str) -> Self {
+        let extension = Path::new(name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("gz") | Some("gzip") => Compression::Gzip,
+            Some("zst") | Some("zstd") => Compression::Zstd,
+            Some("zip") => Compression::Zip,
+            _ => Compression::Plain,
+        }
+    }
+}
+
+impl DataSource {
+    pub fn path(path: impl Into<PathBuf>) -> Self {
+        DataSource::Path(path.into())
+    }
+
+    pub fn reader(reader: impl Read + 'static) -> Self {
+        DataSource::Reader(Box::new(reader))
+    }
+
+    /// A URL fetched with the default [`crate::fetch::FetchOptions`].
+    #[cfg(feature = "url")]
+    pub fn url(url: impl Into<String>) -> Self {
+        DataSource::url_with(url, crate::fetch::FetchOptions::default())
+    }
+
+    #[cfg(feature = "url")]
+    pub fn url_with(url: impl Into<String>, options: crate::fetch::FetchOptions) -> Self {
+        DataSource::Url {
+            url: url.into(),
+            options,
+        }
+    }
+
+    /// Picks `member` out of the zip archive behind `archive`.
+    pub fn zip_member(archive: DataSource, member: impl Into<String>) -> Self {
+        DataSource::ZipMember {
+            archive: Box::new(archive),
+            member: member.into(),
+        }
+    }
+
+    /// Opens the source for reading, decompressing gzip, zstd and zip
+    /// input. The format is detected from the magic bytes; the file
+    /// extension only decides when the input is too short to sniff, so a
+    /// plain CSV named `*.csv.gz` still reads as plain.
+    ///
+    /// Gzip and zstd are decoded while streaming. Zip archives need random
+    /// access: a file on disk is read in place, while other sources (stdin,
+    /// a URL, a reader) are first buffered whole in memory. An archive with
+    /// several members needs [`DataSource::zip_member`] to pick one.
+    pub fn open(self) -> Result<Box<dyn Read>> {
+        let hint = self.name().map_or(Compression::Plain, |name| {
+            Compression::from_extension(&name)
+        });
+        match self {
+            DataSource::ZipMember { archive, member } => match *archive {
+                DataSource::Path(path) => open_zip(open_file(&path)?, Some(&member)),
+                archive => open_zip(buffered(archive.open_raw()?)?, Some(&member)),
+            },
+            DataSource::Path(path) => {
+                decompress(BufReader::new(open_file(&path)?), hint, |reader| {
+                    open_zip(reader, None)
+                })
+            }
+            source => decompress(BufReader::new(source.open_raw()?), hint, |reader| {
+                open_zip(buffered(reader)?, None)
+            }),
+        }
+    }
+
+    /// File name or URL, when there is one.
+    fn name(&self) -> Option<String> {
+        match self {
+            DataSource::Path(path) => Some(path.to_string_lossy().into_owned()),
+            #[cfg(feature = "url")]
+            DataSource::Url { url, .. } => Some(url.clone()),
+            _ => None,
+        }
+    }
+
+    /// The bytes as stored, without decompression.
+    fn open_raw(self) -> Result<Box<dyn Read>> {
+        match self {
+            DataSource::Path(path) => Ok(Box::new(open_file(&path)?)),
+            DataSource::Stdin => Ok(Box::new(io::stdin().lock())),
+            DataSource::Reader(reader) => Ok(reader),
+            #[cfg(feature = "url")]
+            DataSource::Url { url, options } => Ok(crate::fetch::fetch_reader(&url, &options)?),
+            zip @ DataSource::ZipMember { .. } => zip.open(),
+        }
+    }
+}
+
+fn open_file(path: &Path) -> Result<File> {
+    File::open(path).with_context(|| format!("failed to open {}", path.display()))
+}
+
+/// Reads all of `raw` into memory, for zip archives that are not files.
+fn buffered(mut raw: impl Read) -> Result<Cursor<Vec<u8>>> {
+    let mut bytes = Vec::new();
+    raw.read_to_end(&mut bytes)?;
+    Ok(Cursor::new(bytes))
+}
+
+fn decompress<R: Read + 'static>(
+    mut reader: BufReader<R>,
+    hint: Compression,
+    open_zip: impl FnOnce(BufReader<R>) -> Result<Box<dyn Read>>,
+) -> Result<Box<dyn Read>> {
+    let head = reader.fill_buf()?;
+    let compression = match Compression::from_magic(head) {
+        Compression::Plain if head.len() < MAGIC_LEN => hint,
+        detected => detected,
+    };
+    match compression {
+        Compression::Plain => Ok(Box::new(reader)),
+        Compression::Gzip => Ok(Box::new(MultiGzDecoder::new(reader))),
+        Compression::Zstd => Ok(Box::new(zstd::Decoder::with_buffer(reader)?)),
+        Compression::Zip => open_zip(reader),
+    }
+}
+
+/// Streams one member of the archive in `reader`, checking its CRC at the
+/// end.
+fn open_zip<R: Read + Seek + 'static>(reader: R, member: Option<&str>) -> Result<Box<dyn Read>> {
+    let mut archive = zip::ZipArchive::new(reader).context("invalid zip archive")?;
+
+    let name = match member {
+        Some(name) => name.to_string(),
+        None => {
+            let files: Vec<&str> = archive.file_names().filter(|n| !n.ends_with('/')).collect();
+            match files.as_slice() {
+                [only] => only.to_string(),
+                _ => bail!(
+                    "zip archive has {} files {:?}; pick one with DataSource::zip_member",
+                    files.len(),
+                    files
+                ),
+            }
+        }
+    };
+    // `ZipFile` borrows the archive, so note where the member's data lies
+    // and read it from the underlying reader instead.
+    let (start, size, method, crc32) = {
+        let file = archive
+            .by_name(&name)
+            .with_context(|| format!("no member '{}' in zip archive", name))?;
+        (
+            file.data_start(),
+            file.compressed_size(),
+            file.compression(),
+            file.crc32(),
+        )
+    };
+    let mut reader = archive.into_inner();
+    reader.seek(SeekFrom::Start(start))?;
+    let data = reader.take(size);
+    let contents: Box<dyn Read> = match method {
+        CompressionMethod::Stored => Box::new(data),
+        CompressionMethod::Deflated => Box::new(DeflateDecoder::new(data)),
+        other => bail!(
+            "zip member '{}' uses unsupported {} compression",
+            name,
+            other
+        ),
+    };
+    Ok(Box::new(CheckedMember {
+        reader: CrcReader::new(contents),
+        crc32,
+    }))
+}
+
+/// A zip member's contents, failing at the end if the CRC does not match.
+struct CheckedMember {
+    reader: CrcReader<Box<dyn Read>>,
+    crc32: u32,
+}
+
+impl Read for CheckedMember {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        if n == 0 && !buf.is_empty() && self.reader.crc().sum() != self.crc32 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "zip member failed its CRC check",
+            ));
+        }
+        Ok(n)
+    }
+}
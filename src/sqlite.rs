@@ -0,0 +1,258 @@
+//! SQLite source and sink for [`Table`]s, salary records and statistics.
+//!
+//! Column types come from the declared SQL type when it names one of the
+//! table types (`INTEGER`, `REAL`, `TEXT`, `BOOLEAN`, `DATE`, or anything
+//! with the same SQLite affinity), and are inferred from the stored values
+//! otherwise. Requires the `sqlite` feature.
+
+use crate::dataset::SalaryRecord;
+use crate::descriptive::Stat;
+use crate::table::{Column, DataType, Table};
+use anyhow::{bail, Context, Result};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter};
+
+pub use rusqlite::Connection;
+
+/// What [`write_table`] does when the target table already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WriteMode {
+    /// Fail if the table exists.
+    #[default]
+    Create,
+    /// Drop the existing table first.
+    Replace,
+    /// Insert into the existing table, creating it if needed.
+    Append,
+}
+
+/// Runs `sql` and collects the result set into a table.
+pub fn read_query(conn: &Connection, sql: &str) -> Result<Table> {
+    let mut stmt = conn
+        .prepare(sql)
+        .with_context(|| format!("failed to prepare '{}'", sql))?;
+    let names: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+    let declared: Vec<Option<DataType>> = stmt
+        .columns()
+        .iter()
+        .map(|column| column.decl_type().and_then(declared_type))
+        .collect();
+
+    let mut cells: Vec<Vec<SqlValue>> = vec![Vec::new(); names.len()];
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        for (i, column) in cells.iter_mut().enumerate() {
+            column.push(row.get(i)?);
+        }
+    }
+
+    let mut table = Table::new();
+    for ((name, declared), values) in names.into_iter().zip(declared).zip(cells) {
+        let dtype = declared.unwrap_or_else(|| infer_type(&values));
+        let column = to_column(dtype, values).with_context(|| format!("column '{}'", name))?;
+        table.add_column(name, column)?;
+    }
+    Ok(table)
+}
+
+/// Reads every row of the table `name`.
+pub fn read_table(conn: &Connection, name: &str) -> Result<Table> {
+    read_query(conn, &format!("SELECT * FROM {}", quote(name)))
+}
+
+/// Salary records from a query whose columns follow `ds_salaries.csv`;
+/// see [`Table::to_records`].
+pub fn load_records(conn: &Connection, sql: &str) -> Result<Vec<SalaryRecord>> {
+    read_query(conn, sql)?.to_records()
+}
+
+/// Stores `table` as the SQLite table `name`, in one transaction.
+pub fn write_table(
+    conn: &mut Connection,
+    name: &str,
+    table: &Table,
+    mode: WriteMode,
+) -> Result<()> {
+    let tx = conn.transaction()?;
+    if mode == WriteMode::Replace {
+        tx.execute(&format!("DROP TABLE IF EXISTS {}", quote(name)), [])?;
+    }
+    let columns: Vec<String> = table
+        .columns()
+        .map(|(column, values)| format!("{} {}", quote(column), sql_type(values.dtype())))
+        .collect();
+    let if_not_exists = if mode == WriteMode::Append {
+        "IF NOT EXISTS "
+    } else {
+        ""
+    };
+    tx.execute(
+        &format!(
+            "CREATE TABLE {}{} ({})",
+            if_not_exists,
+            quote(name),
+            columns.join(", ")
+        ),
+        [],
+    )
+    .with_context(|| format!("failed to create table '{}'", name))?;
+
+    {
+        let names: Vec<String> = table.column_names().iter().map(|n| quote(n)).collect();
+        let placeholders = vec!["?"; names.len()].join(", ");
+        let mut insert = tx.prepare(&format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            quote(name),
+            names.join(", "),
+            placeholders
+        ))?;
+        for i in 0..table.n_rows() {
+            let row = table.columns().map(|(_, column)| to_sql(column, i));
+            insert.execute(params_from_iter(row))?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Appends named statistics to the table `name` under the label `run`
+/// (a snapshot date, a git hash, ...), creating the table on first use.
+///
+/// The table has the columns `run`, `statistic`, `value` and `excluded`,
+/// so results from several runs can be compared with plain SQL.
+pub fn write_stats(
+    conn: &mut Connection,
+    name: &str,
+    run: &str,
+    stats: &[(&str, Stat)],
+) -> Result<()> {
+    let tx = conn.transaction()?;
+    tx.execute(
+        &format!(
+            "CREATE TABLE IF NOT EXISTS {} \
+             (run TEXT NOT NULL, statistic TEXT NOT NULL, value REAL, excluded INTEGER NOT NULL)",
+            quote(name)
+        ),
+        [],
+    )?;
+    {
+        let mut insert = tx.prepare(&format!(
+            "INSERT INTO {} (run, statistic, value, excluded) VALUES (?1, ?2, ?3, ?4)",
+            quote(name)
+        ))?;
+        for (statistic, stat) in stats {
+            // NaN (from MissingPolicy::Propagate) is stored as NULL.
+            let value = Some(stat.value as f64).filter(|v| !v.is_nan());
+            insert.execute(params![run, statistic, value, stat.excluded as i64])?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Double-quotes an SQL identifier.
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn sql_type(dtype: DataType) -> &'static str {
+    match dtype {
+        DataType::Int => "INTEGER",
+        DataType::Float => "REAL",
+        DataType::Str => "TEXT",
+        DataType::Bool => "BOOLEAN",
+        DataType::Date => "DATE",
+    }
+}
+
+/// Table type for a declared SQL type, following SQLite's affinity rules
+/// plus `BOOLEAN` and `DATE`. `None` means "infer from the values".
+fn declared_type(decl: &str) -> Option<DataType> {
+    let decl = decl.to_ascii_uppercase();
+    if decl == "DATE" {
+        Some(DataType::Date)
+    } else if decl.contains("BOOL") {
+        Some(DataType::Bool)
+    } else if decl.contains("INT") {
+        Some(DataType::Int)
+    } else if ["CHAR", "CLOB", "TEXT"].iter().any(|t| decl.contains(t)) {
+        Some(DataType::Str)
+    } else if ["REAL", "FLOA", "DOUB"].iter().any(|t| decl.contains(t)) {
+        Some(DataType::Float)
+    } else {
+        None
+    }
+}
+
+/// Widest type among the values: text beats real beats integer. A column
+/// of only NULLs is text.
+fn infer_type(values: &[SqlValue]) -> DataType {
+    let has = |f: fn(&SqlValue) -> bool| values.iter().any(f);
+    if has(|v| matches!(v, SqlValue::Text(_))) {
+        DataType::Str
+    } else if has(|v| matches!(v, SqlValue::Real(_))) {
+        DataType::Float
+    } else if has(|v| matches!(v, SqlValue::Integer(_))) {
+        DataType::Int
+    } else {
+        DataType::Str
+    }
+}
+
+fn to_column(dtype: DataType, values: Vec<SqlValue>) -> Result<Column> {
+    fn convert<T>(
+        values: Vec<SqlValue>,
+        dtype: DataType,
+        f: impl Fn(&SqlValue) -> Option<T>,
+    ) -> Result<Vec<Option<T>>> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(row, value)| match value {
+                SqlValue::Null => Ok(None),
+                value => match f(value) {
+                    Some(cell) => Ok(Some(cell)),
+                    None => bail!("row {}: {:?} is not a valid {}", row + 1, value, dtype),
+                },
+            })
+            .collect()
+    }
+
+    let column = match dtype {
+        DataType::Int => Column::Int(convert(values, dtype, |v| match *v {
+            SqlValue::Integer(i) => Some(i),
+            _ => None,
+        })?),
+        DataType::Float => Column::Float(convert(values, dtype, |v| match *v {
+            SqlValue::Integer(i) => Some(i as f64),
+            SqlValue::Real(f) => Some(f),
+            _ => None,
+        })?),
+        DataType::Str => Column::Str(convert(values, dtype, |v| match v {
+            SqlValue::Text(s) => Some(s.clone()),
+            SqlValue::Integer(i) => Some(i.to_string()),
+            SqlValue::Real(f) => Some(f.to_string()),
+            _ => None,
+        })?),
+        DataType::Bool => Column::Bool(convert(values, dtype, |v| match *v {
+            SqlValue::Integer(0) => Some(false),
+            SqlValue::Integer(1) => Some(true),
+            _ => None,
+        })?),
+        DataType::Date => Column::Date(convert(values, dtype, |v| match v {
+            SqlValue::Text(s) => crate::schema::parse_date(s),
+            _ => None,
+        })?),
+    };
+    Ok(column)
+}
+
+fn to_sql(column: &Column, i: usize) -> SqlValue {
+    match column {
+        Column::Int(v) => v[i].map_or(SqlValue::Null, SqlValue::Integer),
+        Column::Float(v) => v[i].map_or(SqlValue::Null, SqlValue::Real),
+        Column::Str(v) => v[i].clone().map_or(SqlValue::Null, SqlValue::Text),
+        Column::Bool(v) => v[i].map_or(SqlValue::Null, |b| SqlValue::Integer(b as i64)),
+        Column::Date(v) => v[i].map_or(SqlValue::Null, |d| SqlValue::Text(d.to_string())),
+    }
+}
@@ -0,0 +1,687 @@
+use crate::categories::Category;
+use crate::dataset::SalaryRecord;
+use crate::schema::{InferOptions, Schema};
+use crate::source::DataSource;
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::{Read, Write};
+
+/// Storage type of a [`Column`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataType {
+    Float,
+    Int,
+    Str,
+    Bool,
+    Date,
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DataType::Float => "float",
+            DataType::Int => "int",
+            DataType::Str => "str",
+            DataType::Bool => "bool",
+            DataType::Date => "date",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A single cell, detached from its column.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Float(f64),
+    Int(i64),
+    Str(String),
+    Bool(bool),
+    Date(NaiveDate),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => Ok(()),
+            Value::Float(v) => write!(f, "{}", v),
+            Value::Int(v) => write!(f, "{}", v),
+            Value::Str(v) => f.write_str(v),
+            Value::Bool(v) => write!(f, "{}", v),
+            Value::Date(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl Value {
+    /// Total order for sorting and grouping: numbers compare by value
+    /// (`Int` and `Float` mixed, floats as [`f64::total_cmp`]), values of
+    /// different types by type, and `Null` after everything else.
+    pub fn total_cmp(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::Null, Value::Null) => Ordering::Equal,
+            (Value::Null, _) => Ordering::Greater,
+            (_, Value::Null) => Ordering::Less,
+            (Value::Int(a), Value::Int(b)) => a.cmp(b),
+            (Value::Float(a), Value::Float(b)) => a.total_cmp(b),
+            (Value::Int(a), Value::Float(b)) => (*a as f64).total_cmp(b),
+            (Value::Float(a), Value::Int(b)) => a.total_cmp(&(*b as f64)),
+            (Value::Str(a), Value::Str(b)) => a.cmp(b),
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::Date(a), Value::Date(b)) => a.cmp(b),
+            (a, b) => a.rank().cmp(&b.rank()),
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Value::Int(_) | Value::Float(_) => 0,
+            Value::Bool(_) => 1,
+            Value::Date(_) => 2,
+            Value::Str(_) => 3,
+            Value::Null => 4,
+        }
+    }
+}
+
+/// A [`Value`] usable as a hash key, for grouping and joins. Cells match
+/// by type and value rather than by how they print: floats compare by bit
+/// pattern, with both zeros and every NaN folded together.
+#[derive(Debug, Clone)]
+pub(crate) struct ValueKey(pub(crate) Value);
+
+impl ValueKey {
+    fn float_bits(v: f64) -> u64 {
+        if v.is_nan() {
+            f64::NAN.to_bits()
+        } else if v == 0.0 {
+            0
+        } else {
+            v.to_bits()
+        }
+    }
+}
+
+impl PartialEq for ValueKey {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Value::Float(a), Value::Float(b)) => Self::float_bits(*a) == Self::float_bits(*b),
+            (a, b) => a == b,
+        }
+    }
+}
+
+impl Eq for ValueKey {}
+
+impl Hash for ValueKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(&self.0).hash(state);
+        match &self.0 {
+            Value::Null => {}
+            Value::Float(v) => Self::float_bits(*v).hash(state),
+            Value::Int(v) => v.hash(state),
+            Value::Str(v) => v.hash(state),
+            Value::Bool(v) => v.hash(state),
+            Value::Date(v) => v.hash(state),
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(v: &str) -> Self {
+        Value::Str(v.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(v: String) -> Self {
+        Value::Str(v)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(v: i64) -> Self {
+        Value::Int(v)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(v: i32) -> Self {
+        Value::Int(v.into())
+    }
+}
+
+impl From<f64> for Value {
+    fn from(v: f64) -> Self {
+        Value::Float(v)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(v: f32) -> Self {
+        Value::Float(v.into())
+    }
+}
+
+impl From<bool> for Value {
+    fn from(v: bool) -> Self {
+        Value::Bool(v)
+    }
+}
+
+impl From<NaiveDate> for Value {
+    fn from(v: NaiveDate) -> Self {
+        Value::Date(v)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(v: Option<T>) -> Self {
+        v.map_or(Value::Null, Into::into)
+    }
+}
+
+/// Serializes as the plain JSON value; dates become `YYYY-MM-DD` strings.
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Null => serializer.serialize_none(),
+            Value::Float(v) => serializer.serialize_f64(*v),
+            Value::Int(v) => serializer.serialize_i64(*v),
+            Value::Str(v) => serializer.serialize_str(v),
+            Value::Bool(v) => serializer.serialize_bool(*v),
+            Value::Date(v) => serializer.collect_str(v),
+        }
+    }
+}
+
+/// A typed column. Empty CSV cells are stored as `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    Float(Vec<Option<f64>>),
+    Int(Vec<Option<i64>>),
+    /// Free text and categorical values alike.
+    Str(Vec<Option<String>>),
+    Bool(Vec<Option<bool>>),
+    Date(Vec<Option<NaiveDate>>),
+}
+
+impl Column {
+    pub fn dtype(&self) -> DataType {
+        match self {
+            Column::Float(_) => DataType::Float,
+            Column::Int(_) => DataType::Int,
+            Column::Str(_) => DataType::Str,
+            Column::Bool(_) => DataType::Bool,
+            Column::Date(_) => DataType::Date,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Column::Float(v) => v.len(),
+            Column::Int(v) => v.len(),
+            Column::Str(v) => v.len(),
+            Column::Bool(v) => v.len(),
+            Column::Date(v) => v.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of missing cells.
+    pub fn null_count(&self) -> usize {
+        (0..self.len()).filter(|&i| self.is_null(i)).count()
+    }
+
+    pub fn is_null(&self, i: usize) -> bool {
+        match self {
+            Column::Float(v) => v[i].is_none(),
+            Column::Int(v) => v[i].is_none(),
+            Column::Str(v) => v[i].is_none(),
+            Column::Bool(v) => v[i].is_none(),
+            Column::Date(v) => v[i].is_none(),
+        }
+    }
+
+    /// Cell `i` as a [`Value`]. Panics if `i` is out of bounds.
+    pub fn get(&self, i: usize) -> Value {
+        match self {
+            Column::Float(v) => v[i].map_or(Value::Null, Value::Float),
+            Column::Int(v) => v[i].map_or(Value::Null, Value::Int),
+            Column::Str(v) => v[i].clone().map_or(Value::Null, Value::Str),
+            Column::Bool(v) => v[i].map_or(Value::Null, Value::Bool),
+            Column::Date(v) => v[i].map_or(Value::Null, Value::Date),
+        }
+    }
+
+    /// Numeric cells widened to `f64`. Fails for non-numeric columns.
+    pub fn to_f64(&self) -> Result<Vec<Option<f64>>> {
+        match self {
+            Column::Float(v) => Ok(v.clone()),
+            Column::Int(v) => Ok(v.iter().map(|x| x.map(|x| x as f64)).collect()),
+            other => bail!("expected a numeric column, found {}", other.dtype()),
+        }
+    }
+
+    /// Non-missing numeric cells widened to `f64`.
+    pub fn f64_values(&self) -> Result<Vec<f64>> {
+        Ok(self.to_f64()?.into_iter().flatten().collect())
+    }
+
+    /// String cells. Fails for non-string columns.
+    pub fn as_str(&self) -> Result<&[Option<String>]> {
+        match self {
+            Column::Str(v) => Ok(v),
+            other => bail!("expected a str column, found {}", other.dtype()),
+        }
+    }
+
+    /// A new column holding the cells at `indices`, in that order.
+    pub fn take(&self, indices: &[usize]) -> Column {
+        match self {
+            Column::Float(v) => Column::Float(indices.iter().map(|&i| v[i]).collect()),
+            Column::Int(v) => Column::Int(indices.iter().map(|&i| v[i]).collect()),
+            Column::Str(v) => Column::Str(indices.iter().map(|&i| v[i].clone()).collect()),
+            Column::Bool(v) => Column::Bool(indices.iter().map(|&i| v[i]).collect()),
+            Column::Date(v) => Column::Date(indices.iter().map(|&i| v[i]).collect()),
+        }
+    }
+
+    /// [`Column::take`] where `None` gives a missing cell.
+    pub fn take_opt(&self, indices: &[Option<usize>]) -> Column {
+        match self {
+            Column::Float(v) => {
+                Column::Float(indices.iter().map(|i| i.and_then(|i| v[i])).collect())
+            }
+            Column::Int(v) => Column::Int(indices.iter().map(|i| i.and_then(|i| v[i])).collect()),
+            Column::Str(v) => Column::Str(
+                indices
+                    .iter()
+                    .map(|i| i.and_then(|i| v[i].clone()))
+                    .collect(),
+            ),
+            Column::Bool(v) => Column::Bool(indices.iter().map(|i| i.and_then(|i| v[i])).collect()),
+            Column::Date(v) => Column::Date(indices.iter().map(|i| i.and_then(|i| v[i])).collect()),
+        }
+    }
+}
+
+impl From<Vec<Option<f64>>> for Column {
+    fn from(values: Vec<Option<f64>>) -> Self {
+        Column::Float(values)
+    }
+}
+
+/// Widens `f32` results such as [`crate::features::standardize_salary`].
+impl From<Vec<Option<f32>>> for Column {
+    fn from(values: Vec<Option<f32>>) -> Self {
+        Column::Float(values.into_iter().map(|v| v.map(f64::from)).collect())
+    }
+}
+
+impl From<Vec<i64>> for Column {
+    fn from(values: Vec<i64>) -> Self {
+        Column::Int(values.into_iter().map(Some).collect())
+    }
+}
+
+/// For 0/1 and index features such as
+/// [`crate::features::create_us_based_feature`].
+impl From<Vec<usize>> for Column {
+    fn from(values: Vec<usize>) -> Self {
+        Column::Int(values.into_iter().map(|v| Some(v as i64)).collect())
+    }
+}
+
+impl From<Vec<Option<String>>> for Column {
+    fn from(values: Vec<Option<String>>) -> Self {
+        Column::Str(values)
+    }
+}
+
+/// A set of equally long, named columns loaded from any CSV header.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Table {
+    names: Vec<String>,
+    columns: Vec<Column>,
+}
+
+impl Table {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a table from `(name, column)` pairs.
+    pub fn from_columns<S: Into<String>>(columns: Vec<(S, Column)>) -> Result<Self> {
+        let mut table = Table::new();
+        for (name, column) in columns {
+            table.add_column(name, column)?;
+        }
+        Ok(table)
+    }
+
+    /// Appends a column. Names must be unique and lengths must match.
+    pub fn add_column(&mut self, name: impl Into<String>, column: Column) -> Result<()> {
+        let name = name.into();
+        if self.names.contains(&name) {
+            bail!("duplicate column '{}'", name);
+        }
+        if !self.columns.is_empty() && column.len() != self.n_rows() {
+            bail!(
+                "column '{}' has {} rows, expected {}",
+                name,
+                column.len(),
+                self.n_rows()
+            );
+        }
+        self.names.push(name);
+        self.columns.push(column);
+        Ok(())
+    }
+
+    pub fn n_rows(&self) -> usize {
+        self.columns.first().map_or(0, Column::len)
+    }
+
+    pub fn n_cols(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn column_names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// `(name, column)` pairs in table order.
+    pub fn columns(&self) -> impl Iterator<Item = (&str, &Column)> {
+        self.names.iter().map(String::as_str).zip(&self.columns)
+    }
+
+    pub fn column(&self, name: &str) -> Result<&Column> {
+        match self.names.iter().position(|n| n == name) {
+            Some(i) => Ok(&self.columns[i]),
+            None => bail!("unknown column '{}'", name),
+        }
+    }
+
+    /// Row `i` as one [`Value`] per column.
+    pub fn row(&self, i: usize) -> Vec<Value> {
+        self.columns.iter().map(|column| column.get(i)).collect()
+    }
+
+    /// A table with only the named columns, in the given order.
+    pub fn select(&self, names: &[&str]) -> Result<Table> {
+        let mut table = Table::new();
+        for &name in names {
+            table.add_column(name, self.column(name)?.clone())?;
+        }
+        Ok(table)
+    }
+
+    /// A table with the rows at `indices`, in that order.
+    pub fn take(&self, indices: &[usize]) -> Table {
+        Table {
+            names: self.names.clone(),
+            columns: self.columns.iter().map(|c| c.take(indices)).collect(),
+        }
+    }
+
+    /// Keeps the rows where `mask` is true.
+    pub fn filter(&self, mask: &[bool]) -> Result<Table> {
+        if mask.len() != self.n_rows() {
+            bail!(
+                "mask has {} entries, table has {} rows",
+                mask.len(),
+                self.n_rows()
+            );
+        }
+        let indices: Vec<usize> = (0..mask.len()).filter(|&i| mask[i]).collect();
+        Ok(self.take(&indices))
+    }
+
+    /// Reads a CSV with a header row, inferring each column's type with
+    /// the default [`InferOptions`].
+    pub fn from_reader<R: Read>(rdr: R) -> Result<Table> {
+        Table::from_reader_with(rdr, &InferOptions::default())
+    }
+
+    /// Reads a CSV with a header row, inferring the schema from every row.
+    /// The whole input is in memory anyway, so `options.sample_rows` does
+    /// not apply and a late `4.5` makes an otherwise integer column float.
+    pub fn from_reader_with<R: Read>(rdr: R, options: &InferOptions) -> Result<Table> {
+        let (headers, rows) = crate::schema::read_raw(rdr)?;
+        let schema = crate::schema::infer_rows(&headers, &rows, options)?;
+        crate::schema::build_table(&schema, &rows)
+    }
+
+    /// Reads a CSV with a header row using a known schema.
+    pub fn from_reader_with_schema<R: Read>(rdr: R, schema: &Schema) -> Result<Table> {
+        let (headers, rows) = crate::schema::read_raw(rdr)?;
+        schema.check_headers(&headers)?;
+        crate::schema::build_table(schema, &rows)
+    }
+
+    /// Reads the CSV behind `source`; see [`Table::from_reader`].
+    pub fn from_source(source: DataSource) -> Result<Table> {
+        Table::from_reader(source.open()?)
+    }
+
+    /// Writes the table as CSV with a header row. Missing cells are empty.
+    pub fn write_csv<W: Write>(&self, writer: W) -> Result<()> {
+        let mut wtr = csv::Writer::from_writer(writer);
+        wtr.write_record(&self.names)?;
+        for i in 0..self.n_rows() {
+            wtr.write_record(self.columns.iter().map(|c| c.get(i).to_string()))?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+
+    /// Salary records from a table with the `ds_salaries.csv` columns, e.g.
+    /// one loaded from Parquet. Extra columns are ignored and absent
+    /// optional ones read as missing; category columns must hold the CSV
+    /// codes. Numeric fields accept int or float columns, so a `work_year`
+    /// stored as `2023.0` is fine. Errors name the offending row.
+    pub fn to_records(&self) -> Result<Vec<SalaryRecord>> {
+        fn number(table: &Table, name: &str, i: usize) -> Result<Option<f64>> {
+            match table
+                .column(name)
+                .map_or(Value::Null, |column| column.get(i))
+            {
+                Value::Null => Ok(None),
+                Value::Float(v) => Ok(Some(v)),
+                Value::Int(v) => Ok(Some(v as f64)),
+                other => bail!("'{}' should be a number, found '{}'", name, other),
+            }
+        }
+        fn text(table: &Table, name: &str, i: usize) -> Result<Option<String>> {
+            match table
+                .column(name)
+                .map_or(Value::Null, |column| column.get(i))
+            {
+                Value::Null => Ok(None),
+                Value::Str(s) => Ok(Some(s)),
+                other => bail!("'{}' should be text, found '{}'", name, other),
+            }
+        }
+        fn code<C: Category>(table: &Table, name: &str, i: usize) -> Result<Option<C>> {
+            text(table, name, i)?
+                .map(|code| C::from_code(&code))
+                .transpose()
+        }
+        fn required<T>(value: Option<T>, name: &str) -> Result<T> {
+            value.with_context(|| format!("missing '{}'", name))
+        }
+
+        let record = |i: usize| -> Result<SalaryRecord> {
+            let year = required(number(self, "work_year", i)?, "work_year")?;
+            if year.fract() != 0.0 || year < i32::MIN as f64 || year > i32::MAX as f64 {
+                bail!("'work_year' should be a whole year, found {}", year);
+            }
+            let float = |name: &str| -> Result<Option<f32>> {
+                Ok(number(self, name, i)?.map(|v| v as f32))
+            };
+            Ok(SalaryRecord {
+                work_year: year as i32,
+                experience_level: required(code(self, "experience_level", i)?, "experience_level")?,
+                employment_type: required(code(self, "employment_type", i)?, "employment_type")?,
+                job_title: required(text(self, "job_title", i)?, "job_title")?,
+                salary: float("salary")?,
+                salary_currency: text(self, "salary_currency", i)?,
+                salary_in_usd: float("salary_in_usd")?,
+                employee_residence: text(self, "employee_residence", i)?,
+                remote_ratio: float("remote_ratio")?,
+                company_location: text(self, "company_location", i)?,
+                company_size: code(self, "company_size", i)?,
+            })
+        };
+        (0..self.n_rows())
+            .map(|i| record(i).with_context(|| format!("row {}", i)))
+            .collect()
+    }
+
+    /// Column view of already parsed salary records.
+    pub fn from_records(records: &[SalaryRecord]) -> Table {
+        fn strings(records: &[SalaryRecord], f: fn(&SalaryRecord) -> Option<&String>) -> Column {
+            Column::Str(records.iter().map(|r| f(r).cloned()).collect())
+        }
+        fn codes<C: Category>(
+            records: &[SalaryRecord],
+            f: fn(&SalaryRecord) -> Option<C>,
+        ) -> Column {
+            Column::Str(
+                records
+                    .iter()
+                    .map(|r| f(r).map(|c| c.code().to_string()))
+                    .collect(),
+            )
+        }
+        fn floats(records: &[SalaryRecord], f: fn(&SalaryRecord) -> Option<f32>) -> Column {
+            Column::Float(records.iter().map(|r| f(r).map(f64::from)).collect())
+        }
+
+        let columns = vec![
+            (
+                "work_year",
+                Column::Int(records.iter().map(|r| Some(r.work_year as i64)).collect()),
+            ),
+            (
+                "experience_level",
+                codes(records, |r| Some(r.experience_level)),
+            ),
+            (
+                "employment_type",
+                codes(records, |r| Some(r.employment_type)),
+            ),
+            ("job_title", strings(records, |r| Some(&r.job_title))),
+            ("salary", floats(records, |r| r.salary)),
+            (
+                "salary_currency",
+                strings(records, |r| r.salary_currency.as_ref()),
+            ),
+            ("salary_in_usd", floats(records, |r| r.salary_in_usd)),
+            (
+                "employee_residence",
+                strings(records, |r| r.employee_residence.as_ref()),
+            ),
+            ("remote_ratio", floats(records, |r| r.remote_ratio)),
+            (
+                "company_location",
+                strings(records, |r| r.company_location.as_ref()),
+            ),
+            ("company_size", codes(records, |r| r.company_size)),
+        ];
+        Table::from_columns(columns).expect("record columns share one length")
+    }
+}
+
+/// A text grid with a header row; missing cells are blank. A precision,
+/// as in `{:.2}`, applies to float cells.
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.columns.is_empty() {
+            return Ok(());
+        }
+        let header: Vec<&str> = self.names.iter().map(String::as_str).collect();
+        let rows: Vec<Vec<String>> = (0..self.n_rows())
+            .map(|i| {
+                self.columns
+                    .iter()
+                    .map(|column| match (column.get(i), f.precision()) {
+                        (Value::Float(v), Some(precision)) => format!("{:.*}", precision, v),
+                        (value, _) => value.to_string(),
+                    })
+                    .collect()
+            })
+            .collect();
+        write_grid(f, &header, &rows, false)
+    }
+}
+
+/// Serializes row by row, each row as a map from column name to value, so
+/// a table comes out in the same shape as a list of records.
+impl Serialize for Table {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut rows = serializer.serialize_seq(Some(self.n_rows()))?;
+        for i in 0..self.n_rows() {
+            rows.serialize_element(&TableRow { table: self, i })?;
+        }
+        rows.end()
+    }
+}
+
+struct TableRow<'a> {
+    table: &'a Table,
+    i: usize,
+}
+
+impl Serialize for TableRow<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut row = serializer.serialize_map(Some(self.table.n_cols()))?;
+        for (name, column) in self.table.columns() {
+            row.serialize_entry(name, &column.get(self.i))?;
+        }
+        row.end()
+    }
+}
+
+/// Pads every column to its widest cell: names left-aligned, numbers
+/// right-aligned. With `text_last`, the last column is free text and is
+/// written as is.
+pub(crate) fn write_grid(
+    f: &mut fmt::Formatter<'_>,
+    header: &[&str],
+    rows: &[Vec<String>],
+    text_last: bool,
+) -> fmt::Result {
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .chain([header[i].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let header: Vec<String> = header.iter().map(|h| h.to_string()).collect();
+    for row in std::iter::once(&header).chain(rows) {
+        let last = row.len() - 1;
+        let cells: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| match i {
+                0 => format!("{:<width$}", cell, width = widths[i]),
+                i if text_last && i == last => cell.clone(),
+                _ => format!("{:>width$}", cell, width = widths[i]),
+            })
+            .collect();
+        writeln!(f, "{}", cells.join("  "))?;
+    }
+    Ok(())
+}
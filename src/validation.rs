@@ -0,0 +1,286 @@
+//! Rule-based checks that run on loaded salary records.
+//!
+//! A [`Validator`] holds named [`Rule`]s, either the built-in ones or any
+//! closure, and reports every violation per rule. In
+//! [`ValidationMode::Strict`] a dataset with violations is an error.
+
+use crate::dataset::SalaryRecord;
+use anyhow::Result;
+use chrono::{DateTime, Datelike};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Earliest `work_year` accepted by [`Validator::standard`].
+pub const MIN_WORK_YEAR: i32 = 2000;
+
+/// Values allowed in `remote_ratio`: on-site, hybrid and fully remote.
+pub const REMOTE_RATIOS: [f32; 3] = [0.0, 50.0, 100.0];
+
+/// Officially assigned ISO 3166-1 alpha-2 country codes, sorted.
+const ISO_3166_ALPHA_2: [&str; 249] = [
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS",
+    "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN",
+    "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "EH", "ER", "ES", "ET", "FI", "FJ", "FK", "FM", "FO", "FR", "GA", "GB", "GD", "GE", "GF",
+    "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY", "HK", "HM",
+    "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT", "JE", "JM",
+    "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ", "LA", "LB", "LC",
+    "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK",
+    "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA",
+    "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF", "PG",
+    "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS",
+    "ST", "SV", "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO",
+    "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG", "VI",
+    "VN", "VU", "WF", "WS", "YE", "YT", "ZA", "ZM", "ZW",
+];
+
+/// Whether `code` is an assigned ISO 3166-1 alpha-2 code (upper case).
+pub fn is_country_code(code: &str) -> bool {
+    ISO_3166_ALPHA_2.binary_search(&code).is_ok()
+}
+
+type Check = dyn Fn(&SalaryRecord) -> Result<(), String> + Send + Sync;
+
+/// A named check on one record. The check returns `Err` with a message
+/// describing the problem.
+pub struct Rule {
+    name: String,
+    check: Box<Check>,
+}
+
+impl Rule {
+    /// A rule from any closure.
+    pub fn new(
+        name: impl Into<String>,
+        check: impl Fn(&SalaryRecord) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        Rule {
+            name: name.into(),
+            check: Box::new(check),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// `salary_in_usd`, when present, is positive.
+    pub fn positive_salary_in_usd() -> Self {
+        Rule::new("positive_salary_in_usd", |record| {
+            match record.salary_in_usd {
+                Some(salary) if salary <= 0.0 => Err(format!("salary_in_usd is {}", salary)),
+                _ => Ok(()),
+            }
+        })
+    }
+
+    /// `remote_ratio`, when present, is one of [`REMOTE_RATIOS`].
+    pub fn remote_ratio() -> Self {
+        Rule::new("remote_ratio", |record| match record.remote_ratio {
+            Some(ratio) if !REMOTE_RATIOS.contains(&ratio) => Err(format!(
+                "remote_ratio is {}, expected one of {:?}",
+                ratio, REMOTE_RATIOS
+            )),
+            _ => Ok(()),
+        })
+    }
+
+    /// `work_year` lies in `min..=max`.
+    pub fn work_year_between(min: i32, max: i32) -> Self {
+        Rule::new("work_year", move |record| {
+            if (min..=max).contains(&record.work_year) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "work_year {} is outside {}..={}",
+                    record.work_year, min, max
+                ))
+            }
+        })
+    }
+
+    /// `employee_residence` and `company_location`, when present, are
+    /// ISO 3166-1 alpha-2 codes.
+    pub fn country_codes() -> Self {
+        Rule::new("country_codes", |record| {
+            let fields = [
+                ("employee_residence", &record.employee_residence),
+                ("company_location", &record.company_location),
+            ];
+            for (field, value) in fields {
+                if let Some(code) = value {
+                    if !is_country_code(code) {
+                        return Err(format!(
+                            "{} '{}' is not an ISO 3166-1 alpha-2 code",
+                            field, code
+                        ));
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+impl fmt::Debug for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rule").field("name", &self.name).finish()
+    }
+}
+
+/// What [`Validator::validate`] does when rules are violated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationMode {
+    /// Return the report and let the caller decide.
+    #[default]
+    Report,
+    /// Return the report as an error if it has any violations.
+    Strict,
+}
+
+/// One record that failed one rule.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Violation {
+    pub rule: String,
+    /// Position of the record in the dataset.
+    pub index: usize,
+    pub message: String,
+}
+
+/// Outcome of running a [`Validator`] over a dataset.
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct ValidationReport {
+    /// Number of records checked.
+    pub checked: usize,
+    /// Names of the rules that ran, in order.
+    pub rules: Vec<String>,
+    pub violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Violations grouped by rule name. Rules without violations are
+    /// included with an empty list.
+    pub fn by_rule(&self) -> BTreeMap<&str, Vec<&Violation>> {
+        let mut groups: BTreeMap<&str, Vec<&Violation>> = self
+            .rules
+            .iter()
+            .map(|r| (r.as_str(), Vec::new()))
+            .collect();
+        for violation in &self.violations {
+            groups
+                .entry(violation.rule.as_str())
+                .or_default()
+                .push(violation);
+        }
+        groups
+    }
+}
+
+/// A per-rule summary: one line per rule with its violation count and the
+/// first offending record.
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} violations in {} records",
+            self.violations.len(),
+            self.checked
+        )?;
+        for (rule, violations) in self.by_rule() {
+            match violations.first() {
+                Some(first) => writeln!(
+                    f,
+                    "  {}: {} (first at record {}: {})",
+                    rule,
+                    violations.len(),
+                    first.index,
+                    first.message
+                )?,
+                None => writeln!(f, "  {}: 0", rule)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
+/// An ordered set of rules plus a [`ValidationMode`].
+#[derive(Debug, Default)]
+pub struct Validator {
+    rules: Vec<Rule>,
+    mode: ValidationMode,
+}
+
+impl Validator {
+    /// A validator without rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All built-in rules, with `work_year` allowed from [`MIN_WORK_YEAR`]
+    /// up to next year.
+    pub fn standard() -> Self {
+        Validator::new()
+            .rule(Rule::positive_salary_in_usd())
+            .rule(Rule::remote_ratio())
+            .rule(Rule::work_year_between(MIN_WORK_YEAR, current_year() + 1))
+            .rule(Rule::country_codes())
+    }
+
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn mode(mut self, mode: ValidationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Runs every rule on every record.
+    ///
+    /// In [`ValidationMode::Strict`] any violation turns the report into
+    /// the error; downcast it to [`ValidationReport`] for the details.
+    pub fn validate(&self, dataset: &[SalaryRecord]) -> Result<ValidationReport> {
+        let mut report = ValidationReport {
+            checked: dataset.len(),
+            rules: self.rules.iter().map(|r| r.name.clone()).collect(),
+            violations: Vec::new(),
+        };
+        for (index, record) in dataset.iter().enumerate() {
+            for rule in &self.rules {
+                if let Err(message) = (rule.check)(record) {
+                    report.violations.push(Violation {
+                        rule: rule.name.clone(),
+                        index,
+                        message,
+                    });
+                }
+            }
+        }
+        if self.mode == ValidationMode::Strict && !report.is_valid() {
+            return Err(report.into());
+        }
+        Ok(report)
+    }
+}
+
+fn current_year() -> i32 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    DateTime::from_timestamp(secs as i64, 0).map_or(MIN_WORK_YEAR, |now| now.year())
+}
@@ -0,0 +1,60 @@
+use rust_stats::categories::{Category, CompanySize, EmploymentType, ExperienceLevel};
+use rust_stats::dataset::load_dataset;
+use rust_stats::features::{group_records_by, one_hot_encode_category, ordinal_encode};
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+#[test]
+fn codes_labels_and_parsing() {
+    assert_eq!(ExperienceLevel::Senior.code(), "SE");
+    assert_eq!(ExperienceLevel::Senior.label(), "Senior");
+    assert_eq!(EmploymentType::Freelance.label(), "Freelance");
+    assert_eq!(CompanySize::Medium.to_string(), "M");
+    assert_eq!(
+        "ex".parse::<ExperienceLevel>().unwrap(),
+        ExperienceLevel::Executive
+    );
+    assert_eq!(CompanySize::from_code("L").unwrap(), CompanySize::Large);
+    let err = "XL".parse::<CompanySize>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"unknown code 'XL', expected one of ["S", "M", "L"]"#
+    );
+}
+
+#[test]
+fn natural_ordering() {
+    use ExperienceLevel::*;
+    let mut levels = vec![Executive, Entry, Senior, Mid];
+    levels.sort();
+    assert_eq!(levels, vec![Entry, Mid, Senior, Executive]);
+    assert!(CompanySize::Small < CompanySize::Large);
+}
+
+#[test]
+fn encoders_work_on_enums() {
+    use CompanySize::*;
+    assert_eq!(
+        ordinal_encode(&[Large, Small, Medium]).unwrap(),
+        vec![2, 0, 1]
+    );
+    assert_eq!(
+        one_hot_encode_category(&[Medium, Large]).unwrap(),
+        vec![vec![0, 1, 0], vec![0, 0, 1]]
+    );
+}
+
+#[test]
+fn records_group_by_enum() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let groups = group_records_by(&dataset, |record| record.experience_level).unwrap();
+    let sizes: Vec<(ExperienceLevel, usize)> = groups
+        .iter()
+        .map(|(level, rows)| (*level, rows.len()))
+        .collect();
+    use ExperienceLevel::*;
+    assert_eq!(
+        sizes,
+        vec![(Entry, 1), (Mid, 2), (Senior, 8), (Executive, 1)]
+    );
+}
@@ -0,0 +1,170 @@
+#![cfg(feature = "columnar")]
+
+mod common;
+
+use arrow::array::{ArrayRef, DictionaryArray, Float32Array, Int32Array, RecordBatch, UInt64Array};
+use arrow::datatypes::Int8Type;
+use arrow::ipc::writer::StreamWriter;
+use chrono::NaiveDate;
+use rust_stats::columnar::{
+    load_parquet_records, read_ipc, read_parquet, table_from_batches, write_ipc, write_parquet,
+};
+use rust_stats::dataset::load_dataset;
+use rust_stats::features::{
+    create_job_title_map, create_us_based_feature, job_title_table, one_hot_encode_job_titles,
+    standardize_salary,
+};
+use rust_stats::source::DataSource;
+use rust_stats::table::{Column, DataType, Table};
+use std::io::Cursor;
+use std::sync::Arc;
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+fn mixed_table() -> Table {
+    Table::from_columns(vec![
+        ("id", Column::Int(vec![Some(1), None, Some(3)])),
+        ("score", Column::Float(vec![Some(0.5), Some(-1.25), None])),
+        (
+            "name",
+            Column::Str(vec![Some("a".into()), None, Some("c".into())]),
+        ),
+        ("flag", Column::Bool(vec![Some(true), Some(false), None])),
+        (
+            "day",
+            Column::Date(vec![NaiveDate::from_ymd_opt(2023, 5, 17), None, None]),
+        ),
+    ])
+    .unwrap()
+}
+
+fn reader(bytes: Vec<u8>) -> DataSource {
+    DataSource::reader(Cursor::new(bytes))
+}
+
+#[test]
+fn parquet_round_trip_keeps_types_and_nulls() {
+    let table = mixed_table();
+    let mut out = Vec::new();
+    write_parquet(&mut out, &table).unwrap();
+    assert_eq!(read_parquet(reader(out)).unwrap(), table);
+}
+
+#[test]
+fn ipc_file_round_trip_keeps_types_and_nulls() {
+    let table = mixed_table();
+    let mut out = Vec::new();
+    write_ipc(&mut out, &table).unwrap();
+    assert_eq!(read_ipc(reader(out)).unwrap(), table);
+}
+
+#[test]
+fn ipc_streams_are_read_too() {
+    let batch = RecordBatch::try_from_iter(vec![(
+        "n",
+        Arc::new(Int32Array::from(vec![1, 2])) as ArrayRef,
+    )])
+    .unwrap();
+    let mut out = Vec::new();
+    let mut writer = StreamWriter::try_new(&mut out, &batch.schema()).unwrap();
+    writer.write(&batch).unwrap();
+    writer.write(&batch).unwrap();
+    writer.finish().unwrap();
+    drop(writer);
+
+    let table = read_ipc(reader(out)).unwrap();
+    assert_eq!(
+        table.column("n").unwrap(),
+        &Column::from(vec![1i64, 2, 1, 2])
+    );
+}
+
+#[test]
+fn records_round_trip_through_parquet() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let dir = common::temp_dir("columnar");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("salaries.parquet");
+    write_parquet(
+        std::fs::File::create(&path).unwrap(),
+        &Table::from_records(&dataset),
+    )
+    .unwrap();
+
+    assert_eq!(
+        load_parquet_records(DataSource::path(&path)).unwrap(),
+        dataset
+    );
+}
+
+#[test]
+fn feature_matrices_export_with_names_and_types() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let mapping = create_job_title_map(&dataset).unwrap();
+    let rows = one_hot_encode_job_titles(&dataset, &mapping).unwrap();
+    let mut features = job_title_table(&rows, &mapping).unwrap();
+    features
+        .add_column("salary_z", standardize_salary(&dataset).unwrap().into())
+        .unwrap();
+    features
+        .add_column(
+            "us_based",
+            create_us_based_feature(&dataset).unwrap().into(),
+        )
+        .unwrap();
+
+    let mut out = Vec::new();
+    write_parquet(&mut out, &features).unwrap();
+    let table = read_parquet(reader(out)).unwrap();
+
+    assert_eq!(table.n_cols(), mapping.len() + 2);
+    assert_eq!(table.column("ML Engineer").unwrap().dtype(), DataType::Int);
+    assert_eq!(table.column("salary_z").unwrap().dtype(), DataType::Float);
+    assert_eq!(
+        table.column("us_based").unwrap(),
+        &Column::from(create_us_based_feature(&dataset).unwrap())
+    );
+    let title_column = &mapping["ML Engineer"];
+    for (i, row) in rows.iter().enumerate() {
+        assert_eq!(
+            table.column("ML Engineer").unwrap().get(i).to_string(),
+            row[*title_column].to_string()
+        );
+    }
+}
+
+#[test]
+fn narrow_and_dictionary_types_are_widened_on_import() {
+    let sizes: DictionaryArray<Int8Type> = vec!["S", "L", "S"].into_iter().collect();
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "year",
+            Arc::new(Int32Array::from(vec![2021, 2022, 2023])) as ArrayRef,
+        ),
+        (
+            "ratio",
+            Arc::new(Float32Array::from(vec![0.5, 1.0, 0.0])) as ArrayRef,
+        ),
+        ("size", Arc::new(sizes) as ArrayRef),
+    ])
+    .unwrap();
+    let table = table_from_batches(&batch.schema(), &[batch]).unwrap();
+
+    assert_eq!(table.column("year").unwrap().dtype(), DataType::Int);
+    assert_eq!(table.column("ratio").unwrap().dtype(), DataType::Float);
+    assert_eq!(
+        table.column("size").unwrap().as_str().unwrap()[1].as_deref(),
+        Some("L")
+    );
+}
+
+#[test]
+fn out_of_range_integers_are_errors() {
+    let batch = RecordBatch::try_from_iter(vec![(
+        "big",
+        Arc::new(UInt64Array::from(vec![u64::MAX])) as ArrayRef,
+    )])
+    .unwrap();
+    let err = table_from_batches(&batch.schema(), &[batch]).unwrap_err();
+    assert!(err.to_string().contains("column 'big'"));
+}
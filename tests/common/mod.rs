@@ -0,0 +1,173 @@
+//! Shared test helpers: small table fixtures and a minimal HTTP/1.1
+//! stand-in server for the fetch tests.
+#![allow(dead_code)]
+
+use rust_stats::table::{Column, Table, Value};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn ok(body: &[u8]) -> Self {
+        Response {
+            status: 200,
+            headers: Vec::new(),
+            body: body.to_vec(),
+        }
+    }
+
+    pub fn status(status: u16) -> Self {
+        Response {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    pub fn header(mut self, name: &'static str, value: &str) -> Self {
+        self.headers.push((name, value.to_string()));
+        self
+    }
+}
+
+pub struct TestServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl TestServer {
+    /// Serves every connection with `handler(n, raw_request)`, where `n`
+    /// counts requests from 0.
+    pub fn start(handler: impl Fn(usize, &str) -> Response + Send + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/ds_salaries.csv", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let request = read_request(&mut stream);
+                let n = {
+                    let mut seen = seen.lock().unwrap();
+                    seen.push(request.clone());
+                    seen.len() - 1
+                };
+                let response = handler(n, &request);
+                let mut head = format!(
+                    "HTTP/1.1 {} Status\r\nContent-Length: {}\r\nConnection: close\r\n",
+                    response.status,
+                    response.body.len()
+                );
+                for (name, value) in &response.headers {
+                    head.push_str(&format!("{}: {}\r\n", name, value));
+                }
+                head.push_str("\r\n");
+                let _ = stream.write_all(head.as_bytes());
+                let _ = stream.write_all(&response.body);
+            }
+        });
+        TestServer { url, requests }
+    }
+
+    /// Raw requests received so far, lowercased for header matching.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+fn read_request(stream: &mut impl Read) -> String {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    while !request.ends_with(b"\r\n\r\n") {
+        match stream.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => request.extend_from_slice(&buf[..n]),
+        }
+    }
+    String::from_utf8_lossy(&request).to_lowercase()
+}
+
+/// A fresh, empty directory under the system temp dir.
+pub fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("rust_stats_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+pub fn strs(values: &[Option<&str>]) -> Column {
+    Column::Str(values.iter().map(|v| v.map(str::to_string)).collect())
+}
+
+pub fn ints(values: &[i64]) -> Vec<Value> {
+    values.iter().map(|&v| Value::Int(v)).collect()
+}
+
+/// Every cell of the column `name`.
+pub fn values(table: &Table, name: &str) -> Vec<Value> {
+    let column = table.column(name).unwrap();
+    (0..column.len()).map(|i| column.get(i)).collect()
+}
+
+/// Six salaries by role, year and country, with a missing role and pay,
+/// plus a `bonus` with a tie, a NaN and a missing value, and row `id`s.
+pub fn salaries() -> Table {
+    Table::from_columns(vec![
+        (
+            "role",
+            strs(&[
+                Some("ds"),
+                Some("ml"),
+                Some("ds"),
+                Some("ds"),
+                Some("ml"),
+                None,
+            ]),
+        ),
+        (
+            "year",
+            Column::from(vec![2023i64, 2022, 2022, 2023, 2023, 2023]),
+        ),
+        (
+            "pay",
+            Column::Float(vec![
+                Some(100.0),
+                Some(80.0),
+                Some(60.0),
+                Some(120.0),
+                None,
+                Some(1.0),
+            ]),
+        ),
+        (
+            "country",
+            strs(&[
+                Some("US"),
+                Some("GB"),
+                Some("US"),
+                Some("GB"),
+                Some("US"),
+                Some("US"),
+            ]),
+        ),
+        (
+            "bonus",
+            Column::Float(vec![
+                Some(100.0),
+                Some(f64::NAN),
+                Some(150.0),
+                Some(70.0),
+                None,
+                Some(100.0),
+            ]),
+        ),
+        ("id", Column::from(vec![0i64, 1, 2, 3, 4, 5])),
+    ])
+    .unwrap()
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use flate2::write::GzEncoder;
+use rust_stats::dataset::{load_dataset, load_source};
+use rust_stats::source::{Compression, DataSource};
+use std::io::{Cursor, Read, Write};
+use zip::write::FileOptions;
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn zip_archive(members: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, data) in members {
+        writer.start_file(*name, FileOptions::default()).unwrap();
+        writer.write_all(data).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+#[test]
+fn detects_formats_from_magic_bytes_and_extensions() {
+    assert_eq!(Compression::from_magic(&gzip(b"x")), Compression::Gzip);
+    assert_eq!(
+        Compression::from_magic(&zstd::encode_all(&b"x"[..], 0).unwrap()),
+        Compression::Zstd
+    );
+    assert_eq!(Compression::from_magic(b"work_year,"), Compression::Plain);
+    assert_eq!(
+        Compression::from_extension("salaries.csv.GZ"),
+        Compression::Gzip
+    );
+    assert_eq!(Compression::from_extension("a.csv.zst"), Compression::Zstd);
+    assert_eq!(Compression::from_extension("a.zip"), Compression::Zip);
+    assert_eq!(Compression::from_extension("a.csv"), Compression::Plain);
+}
+
+#[test]
+fn gzip_file_loads_like_plain_csv() {
+    let dir = common::temp_dir("gzip");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("salaries.csv.gz");
+    std::fs::write(&path, gzip(SAMPLE.as_bytes())).unwrap();
+
+    let dataset = load_source(DataSource::path(&path)).unwrap();
+    assert_eq!(dataset, load_dataset(SAMPLE).unwrap());
+}
+
+#[test]
+fn concatenated_gzip_members_are_all_read() {
+    let (head, tail) = SAMPLE.split_at(SAMPLE.find('\n').unwrap() + 1);
+    let mut data = gzip(head.as_bytes());
+    data.extend(gzip(tail.as_bytes()));
+
+    let dataset = load_source(DataSource::reader(Cursor::new(data))).unwrap();
+    assert_eq!(dataset, load_dataset(SAMPLE).unwrap());
+}
+
+#[test]
+fn zstd_reader_is_detected_without_a_file_name() {
+    let data = zstd::encode_all(SAMPLE.as_bytes(), 3).unwrap();
+    let dataset = load_source(DataSource::reader(Cursor::new(data))).unwrap();
+    assert_eq!(dataset, load_dataset(SAMPLE).unwrap());
+}
+
+#[test]
+fn single_member_zip_is_opened_automatically() {
+    let data = zip_archive(&[("salaries.csv", SAMPLE.as_bytes())]);
+    let dataset = load_source(DataSource::reader(Cursor::new(data))).unwrap();
+    assert_eq!(dataset, load_dataset(SAMPLE).unwrap());
+}
+
+#[test]
+fn multi_member_zip_needs_a_member_name() {
+    let data = zip_archive(&[
+        ("README.txt", b"not a csv"),
+        ("salaries.csv", SAMPLE.as_bytes()),
+    ]);
+
+    let err = load_source(DataSource::reader(Cursor::new(data.clone()))).unwrap_err();
+    assert!(err.to_string().contains("salaries.csv"));
+
+    let source = DataSource::zip_member(
+        DataSource::reader(Cursor::new(data.clone())),
+        "salaries.csv",
+    );
+    assert_eq!(load_source(source).unwrap(), load_dataset(SAMPLE).unwrap());
+
+    let missing = DataSource::zip_member(DataSource::reader(Cursor::new(data)), "other.csv");
+    assert!(load_source(missing)
+        .unwrap_err()
+        .to_string()
+        .contains("no member 'other.csv'"));
+}
+
+#[test]
+fn zip_files_on_disk_are_read_in_place() {
+    let dir = common::temp_dir("zip");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("salaries.zip");
+    let data = zip_archive(&[
+        ("README.txt", b"not a csv"),
+        ("salaries.csv", SAMPLE.as_bytes()),
+    ]);
+    std::fs::write(&path, &data).unwrap();
+
+    let source = DataSource::zip_member(DataSource::path(&path), "salaries.csv");
+    assert_eq!(load_source(source).unwrap(), load_dataset(SAMPLE).unwrap());
+    assert!(load_source(DataSource::path(&path)).is_err());
+
+    let single = dir.join("single.zip");
+    std::fs::write(&single, zip_archive(&[("a.csv", SAMPLE.as_bytes())])).unwrap();
+    let dataset = load_source(DataSource::path(&single)).unwrap();
+    assert_eq!(dataset, load_dataset(SAMPLE).unwrap());
+}
+
+#[test]
+fn corrupt_zip_member_fails_its_crc_check() {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let stored = FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    writer.start_file("salaries.csv", stored).unwrap();
+    writer.write_all(SAMPLE.as_bytes()).unwrap();
+    let mut data = writer.finish().unwrap().into_inner();
+    // Flip a byte of the stored data, just past the 30-byte local header
+    // and the 12-byte name.
+    data[42 + 5] ^= 0x20;
+
+    let mut contents = String::new();
+    let err = DataSource::reader(Cursor::new(data))
+        .open()
+        .unwrap()
+        .read_to_string(&mut contents)
+        .unwrap_err();
+    assert!(err.to_string().contains("CRC"));
+}
+
+#[test]
+fn magic_bytes_win_over_the_extension() {
+    let dir = common::temp_dir("misnamed");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("salaries.csv.gz");
+    std::fs::write(&path, SAMPLE).unwrap();
+
+    let dataset = load_source(DataSource::path(&path)).unwrap();
+    assert_eq!(dataset, load_dataset(SAMPLE).unwrap());
+}
@@ -0,0 +1,96 @@
+use rust_stats::currency::{check_salary_in_usd, convert_salaries, ExchangeRates, MissingRate};
+use rust_stats::dataset::load_dataset;
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+fn rates() -> ExchangeRates {
+    ExchangeRates::from_path("tests/data/exchange_rates.csv").unwrap()
+}
+
+#[test]
+fn yearly_rates_are_averaged() {
+    let rates = rates();
+    assert!((rates.rate("EUR", 2023).unwrap() - 0.93).abs() < 1e-12);
+    assert_eq!(rates.rate("usd", 1999).unwrap(), 1.0);
+    assert_eq!(
+        rates.rate("EUR", 2021).unwrap_err().to_string(),
+        "no EUR exchange rate for 2021"
+    );
+}
+
+#[test]
+fn convert_goes_through_usd() {
+    let rates = rates();
+    let eur = rates.convert(93.0, "EUR", "USD", 2023).unwrap();
+    assert!((eur - 100.0).abs() < 1e-9);
+    let cad = rates.convert(93.0, "EUR", "CAD", 2023).unwrap();
+    assert!((cad - 135.0).abs() < 1e-9);
+}
+
+#[test]
+fn salaries_convert_per_work_year() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let usd = convert_salaries(&dataset, &rates(), "USD").unwrap();
+    assert!(usd.missing_rates.is_empty());
+    assert!((usd.salaries[0].unwrap() - 80000.0 / 0.93).abs() < 0.01);
+    assert_eq!(usd.salaries[1], Some(30000.0));
+    assert!((usd.salaries[10].unwrap() - 40000.0 / 0.88).abs() < 0.01);
+
+    for target in ["JPY", "EUROS"] {
+        let err = convert_salaries(&dataset, &rates(), target).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("no {} exchange rates loaded", target)
+        );
+    }
+}
+
+#[test]
+fn salary_in_usd_is_cross_checked() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let check = check_salary_in_usd(&dataset, &rates(), 0.05);
+    assert!(check.missing_rates.is_empty());
+    assert_eq!(check.mismatches.len(), 1);
+    assert_eq!(check.mismatches[0].index, 10);
+    assert_eq!(check.mismatches[0].provided, 49253.0);
+    assert!(check.mismatches[0].relative_error > 0.05);
+
+    assert!(check_salary_in_usd(&dataset, &rates(), 0.1)
+        .mismatches
+        .is_empty());
+}
+
+#[test]
+fn records_without_a_rate_are_skipped_and_reported() {
+    let mut dataset = load_dataset(SAMPLE).unwrap();
+    dataset[3].salary_currency = Some("JPY".to_string());
+    let missing = MissingRate {
+        index: 3,
+        currency: "JPY".to_string(),
+        year: 2023,
+    };
+
+    let usd = convert_salaries(&dataset, &rates(), "USD").unwrap();
+    assert_eq!(usd.salaries[3], None);
+    assert_eq!(usd.salaries[1], Some(30000.0));
+
+    let check = check_salary_in_usd(&dataset, &rates(), 0.05);
+    assert_eq!(check.mismatches.len(), 1);
+    assert_eq!(check.missing_rates, [missing]);
+    assert_eq!(usd.missing_rates, check.missing_rates);
+
+    // The target currency can lack a rate too: there is no EUR for 2021.
+    let eur = convert_salaries(&dataset, &rates(), "eur").unwrap();
+    assert_eq!(eur.salaries[11], None);
+    let last = eur.missing_rates.last().unwrap();
+    assert_eq!(
+        (last.index, last.currency.as_str(), last.year),
+        (11, "EUR", 2021)
+    );
+}
+
+#[test]
+fn non_positive_rates_are_rejected() {
+    let csv = "date,currency,rate\n2023-01-01,EUR,0\n";
+    assert!(ExchangeRates::from_reader(csv.as_bytes()).is_err());
+}
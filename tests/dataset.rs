@@ -0,0 +1,93 @@
+use rust_stats::categories::{CompanySize, ExperienceLevel};
+use rust_stats::dataset::{load_dataset, load_dataset_with, LoadMode, RowError};
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+#[test]
+fn load_dataset_parses_every_row() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    assert_eq!(dataset.len(), 12);
+    let first = &dataset[0];
+    assert_eq!(first.work_year, 2023);
+    assert_eq!(first.experience_level, ExperienceLevel::Senior);
+    assert_eq!(first.job_title, "Principal Data Scientist");
+    assert_eq!(first.salary_currency.as_deref(), Some("EUR"));
+    assert_eq!(first.salary_in_usd, Some(85847.0));
+    assert_eq!(first.company_size, Some(CompanySize::Large));
+}
+
+#[test]
+fn load_dataset_rejects_malformed_rows() {
+    let csv = "work_year,experience_level,employment_type,job_title,salary,salary_currency,salary_in_usd,employee_residence,remote_ratio,company_location,company_size\n\
+               not-a-year,SE,FT,Data Scientist,1,USD,1,US,0,US,M\n";
+    assert!(load_dataset(csv).is_err());
+}
+
+const BAD_ROWS: &str = "work_year,experience_level,employment_type,job_title,salary,salary_currency,salary_in_usd,employee_residence,remote_ratio,company_location,company_size\n\
+                        2023,SE,FT,Data Scientist,1,USD,1,US,0,US,M\n\
+                        2023,SE,FT,Data Scientist,1,USD,lots,US,0,US,M\n\
+                        2022,SE,FT,Data Scientist,1,USD,1,US,0,US,M\n\
+                        2021,SE,FT\n";
+
+#[test]
+fn strict_mode_stops_at_the_first_bad_row() {
+    let err = load_dataset_with(BAD_ROWS, LoadMode::Strict).unwrap_err();
+    let row = err.downcast_ref::<RowError>().unwrap();
+    assert_eq!(row.line, 3);
+    assert_eq!(row.column.as_deref(), Some("salary_in_usd"));
+}
+
+#[test]
+fn skip_mode_counts_bad_rows() {
+    let loaded = load_dataset_with(BAD_ROWS, LoadMode::Skip).unwrap();
+    assert_eq!(loaded.records.len(), 2);
+    assert_eq!(loaded.report.skipped, 2);
+    assert!(loaded.report.rejected.is_empty());
+}
+
+#[test]
+fn collect_mode_reports_every_bad_row() {
+    let loaded = load_dataset_with(BAD_ROWS, LoadMode::Collect).unwrap();
+    assert_eq!(loaded.records.len(), 2);
+    assert_eq!(
+        loaded.report.rejected,
+        vec![
+            RowError {
+                line: 3,
+                column: Some("salary_in_usd".to_string()),
+                message: "invalid float literal".to_string(),
+            },
+            RowError {
+                line: 5,
+                column: None,
+                message: "expected 11 fields, found 3".to_string(),
+            },
+        ]
+    );
+    assert_eq!(
+        loaded.report.rejected[0].to_string(),
+        "line 3, column 'salary_in_usd': invalid float literal"
+    );
+}
+
+#[test]
+fn empty_optional_cells_load_as_none() {
+    let csv = "work_year,experience_level,employment_type,job_title,salary,salary_currency,salary_in_usd,employee_residence,remote_ratio,company_location,company_size\n\
+               2023,SE,FT,Data Scientist,,,,,,,\n";
+    let record = &load_dataset(csv).unwrap()[0];
+    assert_eq!(record.job_title, "Data Scientist");
+    assert_eq!(record.salary_in_usd, None);
+    assert_eq!(record.company_location, None);
+    assert_eq!(record.company_size, None);
+}
+
+#[test]
+fn unknown_category_codes_are_row_errors() {
+    let csv = "work_year,experience_level,employment_type,job_title,salary,salary_currency,salary_in_usd,employee_residence,remote_ratio,company_location,company_size\n\
+               2023,SR,FT,Data Scientist,1,USD,1,US,0,US,M\n";
+    let loaded = load_dataset_with(csv, LoadMode::Collect).unwrap();
+    assert!(loaded.records.is_empty());
+    let row = &loaded.report.rejected[0];
+    assert_eq!(row.line, 2);
+    assert!(row.message.contains("unknown variant `SR`"));
+}
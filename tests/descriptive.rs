@@ -0,0 +1,65 @@
+use rust_stats::descriptive::{calc_mean, calc_median, calc_std, range};
+
+#[test]
+fn mean_of_small_column() {
+    assert_eq!(calc_mean(&[1.0, 2.0, 3.0, 4.0]).unwrap(), 2.5);
+}
+
+#[test]
+fn median_handles_odd_and_even_lengths() {
+    assert_eq!(calc_median(&[3.0, 1.0, 2.0]).unwrap(), 2.0);
+    assert_eq!(calc_median(&[4.0, 1.0, 3.0, 2.0]).unwrap(), 2.5);
+}
+
+#[test]
+fn std_uses_sample_denominator() {
+    let std = calc_std(&[2.0_f32, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]).unwrap();
+    assert!((std - 2.138_09).abs() < 1e-4);
+}
+
+#[test]
+fn range_is_max_minus_min() {
+    assert_eq!(range(&[5.0, -2.0, 10.0, 3.0]).unwrap(), 12.0);
+}
+
+#[test]
+fn empty_columns_are_errors() {
+    assert!(calc_mean(&[] as &[f32]).is_err());
+    assert!(calc_median(&[] as &[f32]).is_err());
+    assert!(calc_std(&[1.0]).is_err());
+    assert!(range(&[] as &[f32]).is_err());
+}
+
+#[test]
+fn statistics_are_generic_over_numeric_types() {
+    let floats: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0];
+    let mean: f64 = calc_mean(&floats).unwrap();
+    assert_eq!(mean, 2.5);
+
+    let years: Vec<i32> = vec![2020, 2023, 2021, 2022];
+    let mean: f64 = calc_mean(&years).unwrap();
+    assert_eq!(mean, 2021.5);
+    assert_eq!(calc_median(&years).unwrap(), 2021.5);
+    assert_eq!(range(&years).unwrap(), 3.0);
+    assert!((calc_std(&years).unwrap() - 1.290_994).abs() < 1e-6);
+
+    let counts: [u8; 3] = [3, 1, 2];
+    assert_eq!(calc_median(counts).unwrap(), 2.0);
+}
+
+#[test]
+fn statistics_accept_iterators() {
+    let values = (1..=100).map(|v| v as f32 / 10.0);
+    assert!((calc_mean(values.clone()).unwrap() - 5.05).abs() < 1e-5);
+    assert_eq!(range(values.clone()).unwrap(), 9.9);
+    assert!((calc_median(values.filter(|v| *v > 5.0)).unwrap() - 7.55).abs() < 1e-5);
+}
+
+#[test]
+fn integer_sums_do_not_overflow() {
+    let big = [i64::MAX, i64::MAX, i64::MAX];
+    assert_eq!(calc_mean(&big).unwrap(), i64::MAX as f64);
+    assert_eq!(calc_median(&big[..2]).unwrap(), i64::MAX as f64);
+    assert_eq!(range([i64::MIN, i64::MAX]).unwrap(), u64::MAX as f64);
+    assert_eq!(calc_mean([u64::MAX, u64::MAX]).unwrap(), u64::MAX as f64);
+}
@@ -0,0 +1,32 @@
+use rust_stats::distributions::{
+    calc_bern_prob, calc_binom_prob, calc_binom_prob_less, calc_normal_density, calc_normal_prob,
+    calc_unif_prob,
+};
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[test]
+fn discrete_probabilities() {
+    assert!(close(calc_bern_prob(0.6).unwrap(), 0.6));
+    assert!(close(calc_binom_prob(0.6, 10, 8).unwrap(), 0.120_932_352));
+    assert!(close(
+        calc_binom_prob_less(0.6, 10, 8).unwrap(),
+        0.953_642_598_4
+    ));
+}
+
+#[test]
+fn continuous_probabilities() {
+    assert!(close(calc_unif_prob(0.0, 2.0, 0.5).unwrap(), 0.25));
+    assert!(close(calc_normal_prob(0.0, 1.0, 0.0).unwrap(), 0.5));
+    let peak = 1.0 / (2.0 * std::f64::consts::PI).sqrt();
+    assert!(close(calc_normal_density(0.0, 1.0, 0.0).unwrap(), peak));
+}
+
+#[test]
+fn invalid_parameters_are_errors() {
+    assert!(calc_bern_prob(1.5).is_err());
+    assert!(calc_normal_prob(0.0, -1.0, 0.0).is_err());
+}
@@ -0,0 +1,223 @@
+use rust_stats::dataset::load_dataset;
+use rust_stats::expr::{col, Expr};
+use rust_stats::features::{
+    create_feature_where, create_us_based_feature, filter_and_convert, filter_and_convert_where,
+};
+use rust_stats::table::{Column, Table};
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+#[test]
+fn parsed_and_built_expressions_agree() {
+    let parsed: Expr = r#"experience_level in ("SE","EX") and salary_in_usd > 100000"#
+        .parse()
+        .unwrap();
+    let built = col("experience_level")
+        .is_in(["SE", "EX"])
+        .and(col("salary_in_usd").gt(100000));
+    assert_eq!(parsed, built);
+
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let hits = parsed.filter_records(&dataset).unwrap();
+    assert!(!hits.is_empty());
+    for record in hits {
+        assert!(record.salary_in_usd.unwrap() > 100000.0);
+        assert!(["SE", "EX"].contains(&record.experience_level.to_string().as_str()));
+    }
+}
+
+#[test]
+fn precedence_negation_and_nulls() {
+    let expr = Expr::parse("not a = 1 or b is not null and a >= 2.5").unwrap();
+    let expected = (!col("a").eq(1)).or(col("b").is_not_null().and(col("a").ge(2.5)));
+    assert_eq!(expr, expected);
+    // Display output parses back to the same expression.
+    assert_eq!(Expr::parse(&expr.to_string()).unwrap(), expr);
+
+    let table = Table::from_columns(vec![
+        ("a", Column::Int(vec![Some(1), Some(3), None])),
+        (
+            "b",
+            Column::Str(vec![Some("x".into()), None, Some("y".into())]),
+        ),
+    ])
+    .unwrap();
+    assert_eq!(
+        Expr::parse("a > 1").unwrap().mask(&table).unwrap(),
+        [false, true, false]
+    );
+    assert_eq!(
+        Expr::parse("a is null").unwrap().mask(&table).unwrap(),
+        [false, false, true]
+    );
+    assert_eq!(
+        Expr::parse("b not in ('x') AND b IS NOT NULL")
+            .unwrap()
+            .mask(&table)
+            .unwrap(),
+        [false, false, true]
+    );
+    assert_eq!(table.filter_expr(&col("a").le(1.5)).unwrap().n_rows(), 1);
+}
+
+#[test]
+fn date_columns_compare_with_date_text() {
+    let table = Table::from_columns(vec![(
+        "day",
+        Column::Date(vec![
+            chrono::NaiveDate::from_ymd_opt(2022, 12, 31),
+            chrono::NaiveDate::from_ymd_opt(2023, 1, 1),
+        ]),
+    )])
+    .unwrap();
+    let mask = Expr::parse(r#"day >= "2023-01-01""#)
+        .unwrap()
+        .mask(&table)
+        .unwrap();
+    assert_eq!(mask, [false, true]);
+}
+
+#[test]
+fn unknown_columns_and_type_mismatches_are_errors() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let err = Expr::parse("salary_usd > 1")
+        .unwrap()
+        .filter_records(&dataset)
+        .unwrap_err();
+    assert_eq!(err.to_string(), "unknown column 'salary_usd'");
+
+    let err = Expr::parse(r#"salary_in_usd > "high""#)
+        .unwrap()
+        .matches(&dataset[0])
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"type mismatch: column 'salary_in_usd' is float, cannot compare with str "high""#
+    );
+    assert!(Expr::parse("work_year in (2023, 'x')")
+        .unwrap()
+        .matches(&dataset[0])
+        .is_err());
+}
+
+#[test]
+fn syntax_errors_point_at_the_problem() {
+    let err = Expr::parse("salary_in_usd > ").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "expected a value at position 17, found the end"
+    );
+    let err = Expr::parse("a = 1 b = 2").unwrap_err();
+    assert!(err.to_string().contains("position 7"));
+    assert!(Expr::parse("a in (1, 2").is_err());
+    assert!(Expr::parse("a = 'open")
+        .unwrap_err()
+        .to_string()
+        .contains("unterminated"));
+    assert!(Expr::parse("a ~ 1").is_err());
+}
+
+#[test]
+fn features_take_expressions_instead_of_hard_coded_values() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    assert_eq!(
+        create_feature_where(&dataset, &col("company_location").eq("US")).unwrap(),
+        create_us_based_feature(&dataset).unwrap()
+    );
+    assert_eq!(
+        filter_and_convert_where(&dataset, &Expr::parse("experience_level = 'SE'").unwrap())
+            .unwrap(),
+        filter_and_convert(&dataset).unwrap()
+    );
+}
+
+#[test]
+fn nan_cells_never_match() {
+    let table = Table::from_columns(vec![(
+        "salary_in_usd",
+        Column::Float(vec![Some(150_000.0), Some(f64::NAN), None]),
+    )])
+    .unwrap();
+    for text in [
+        "salary_in_usd > 100000",
+        "salary_in_usd < 100000",
+        "salary_in_usd != 1",
+        "salary_in_usd in (150000, 1)",
+        "salary_in_usd not in (1)",
+    ] {
+        let mask = Expr::parse(text).unwrap().mask(&table).unwrap();
+        assert!(!mask[1] && !mask[2], "{}", text);
+    }
+    assert_eq!(
+        Expr::parse("salary_in_usd > 100000")
+            .unwrap()
+            .mask(&table)
+            .unwrap(),
+        [true, false, false]
+    );
+}
+
+#[test]
+fn string_literals_with_quotes_round_trip() {
+    for text in [
+        r#"a "quoted" word"#,
+        "it's",
+        r#"both ' and ""#,
+        r"back\slash\",
+    ] {
+        let expr = col("job_title").eq(text);
+        let parsed = Expr::parse(&expr.to_string()).unwrap();
+        assert_eq!(parsed, expr, "{}", expr);
+    }
+    assert_eq!(Expr::parse(r#"b = 'it\'s'"#).unwrap(), col("b").eq("it's"));
+    assert!(Expr::parse(r#"b = "open\""#).is_err());
+}
+
+#[test]
+fn not_in_skips_missing_cells() {
+    let table = Table::from_columns(vec![(
+        "company_size",
+        Column::Str(vec![Some("S".into()), Some("L".into()), None]),
+    )])
+    .unwrap();
+    let expr = Expr::parse(r#"company_size not in ("S")"#).unwrap();
+    assert_eq!(expr, col("company_size").not_in(["S"]));
+    assert_eq!(Expr::parse(&expr.to_string()).unwrap(), expr);
+    assert_eq!(expr.mask(&table).unwrap(), [false, true, false]);
+    // Negating `in` explicitly still flips every row.
+    assert_eq!(
+        Expr::parse(r#"not company_size in ("S")"#)
+            .unwrap()
+            .mask(&table)
+            .unwrap(),
+        [false, true, true]
+    );
+}
+
+#[test]
+fn is_null_matches_nan_and_literals_must_be_finite() {
+    let table = Table::from_columns(vec![(
+        "x",
+        Column::Float(vec![Some(1.0), Some(f64::NAN), None]),
+    )])
+    .unwrap();
+    assert_eq!(
+        Expr::parse("x is null").unwrap().mask(&table).unwrap(),
+        [false, true, true]
+    );
+    assert_eq!(
+        Expr::parse("x is not null").unwrap().mask(&table).unwrap(),
+        [true, false, false]
+    );
+
+    let err = col("x").eq(f64::INFINITY).mask(&table).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "column 'x': literal inf is not a finite number"
+    );
+    assert!(col("x").lt(f64::NAN).mask(&table).is_err());
+    assert_eq!(
+        Expr::parse("x > 1e999").unwrap_err().to_string(),
+        "expected a finite number at position 5, found '1e999'"
+    );
+}
@@ -0,0 +1,65 @@
+use rust_stats::dataset::load_dataset;
+use rust_stats::features::{
+    create_job_title_map, create_us_based_feature, filter_and_convert, one_hot_encode_job_titles,
+    standardize_salary,
+};
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+#[test]
+fn standardized_salary_has_zero_mean() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let z: Vec<f32> = standardize_salary(&dataset)
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect();
+    assert_eq!(z.len(), dataset.len());
+    let mean: f32 = z.iter().sum::<f32>() / z.len() as f32;
+    assert!(mean.abs() < 1e-5);
+}
+
+#[test]
+fn one_hot_rows_match_the_job_title_map() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let mapping = create_job_title_map(&dataset).unwrap();
+    assert_eq!(mapping.len(), 6);
+
+    let encoded = one_hot_encode_job_titles(&dataset, &mapping).unwrap();
+    for (record, row) in dataset.iter().zip(&encoded) {
+        assert_eq!(row.len(), mapping.len());
+        assert_eq!(row.iter().sum::<i32>(), 1);
+        assert_eq!(row[mapping[&record.job_title]], 1);
+    }
+}
+
+#[test]
+fn unseen_job_titles_are_errors() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    // The last two records are the only Data Analyst and Head of Data.
+    let (train, test) = dataset.split_at(10);
+    let mapping = create_job_title_map(train).unwrap();
+    let err = one_hot_encode_job_titles(test, &mapping).unwrap_err();
+    assert_eq!(
+        format!("{:#}", err),
+        "record 0: job title 'Data Analyst' is not in the mapping"
+    );
+}
+
+#[test]
+fn us_based_feature_flags_us_companies() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let us = create_us_based_feature(&dataset).unwrap();
+    assert_eq!(us, vec![0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 0, 1]);
+}
+
+#[test]
+fn filter_and_convert_keeps_senior_rows() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let rows = filter_and_convert(&dataset).unwrap();
+    assert_eq!(rows.len(), 8);
+    assert_eq!(
+        rows[0],
+        (2023, "Principal Data Scientist".to_string(), Some(85847.0))
+    );
+}
@@ -0,0 +1,213 @@
+#![cfg(feature = "url")]
+
+mod common;
+
+use common::{temp_dir, Response, TestServer};
+use rust_stats::dataset::fetch_dataset_with;
+use rust_stats::fetch::{
+    fetch_async, fetch_reader, fetch_records, fetch_records_async, fetch_with, sha256_hex,
+    FetchError, FetchOptions,
+};
+use rust_stats::source::DataSource;
+use std::io::Read;
+use std::time::Duration;
+
+const BODY: &[u8] = b"work_year,salary\n2023,100\n";
+
+#[test]
+fn etag_revalidation_reuses_the_cached_body() {
+    let server = TestServer::start(|_, request| {
+        if request.contains("if-none-match: \"v1\"") {
+            Response::status(304)
+        } else {
+            Response::ok(BODY).header("ETag", "\"v1\"")
+        }
+    });
+    let options = FetchOptions::new().cache_dir(temp_dir("etag"));
+
+    assert_eq!(fetch_with(&server.url, &options).unwrap(), BODY);
+    assert_eq!(fetch_with(&server.url, &options).unwrap(), BODY);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(!requests[0].contains("if-none-match"));
+    assert!(requests[1].contains("if-none-match: \"v1\""));
+}
+
+#[test]
+fn last_modified_revalidation_and_refresh() {
+    let stamp = "Wed, 21 Oct 2015 07:28:00 GMT";
+    let server = TestServer::start(move |n, _| match n {
+        0 => Response::ok(BODY).header("Last-Modified", stamp),
+        _ => Response::ok(b"work_year,salary\n2024,200\n"),
+    });
+    let options = FetchOptions::new().cache_dir(temp_dir("last_modified"));
+
+    fetch_with(&server.url, &options).unwrap();
+    let refreshed = fetch_with(&server.url, &options).unwrap();
+    assert_eq!(refreshed, b"work_year,salary\n2024,200\n");
+    assert!(server.requests()[1].contains(&format!("if-modified-since: {}", stamp.to_lowercase())));
+}
+
+#[test]
+fn sha256_pin_rejects_unexpected_bodies() {
+    let server = TestServer::start(|_, _| Response::ok(BODY));
+    let good = FetchOptions::new().sha256(sha256_hex(BODY).to_uppercase());
+    assert_eq!(
+        fetch_dataset_with(&server.url, &good).unwrap().as_bytes(),
+        BODY
+    );
+
+    let bad = FetchOptions::new()
+        .cache_dir(temp_dir("pin"))
+        .sha256(sha256_hex(b"something else"));
+    let err = fetch_with(&server.url, &bad).unwrap_err();
+    assert!(format!("{:#}", err).contains("SHA-256 mismatch"));
+    // Nothing was cached, so offline mode has nothing to serve.
+    assert!(fetch_with(&server.url, &bad.offline(true)).is_err());
+}
+
+#[test]
+fn offline_mode_serves_only_from_cache() {
+    let server = TestServer::start(|_, _| Response::ok(BODY));
+    let dir = temp_dir("offline");
+    let online = FetchOptions::new().cache_dir(&dir);
+    fetch_with(&server.url, &online).unwrap();
+
+    let offline = online.offline(true);
+    assert_eq!(fetch_with(&server.url, &offline).unwrap(), BODY);
+    assert_eq!(server.requests().len(), 1);
+
+    let err = fetch_with("http://127.0.0.1:9/never-fetched.csv", &offline).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "offline mode: http://127.0.0.1:9/never-fetched.csv is not in the cache"
+    );
+}
+
+#[test]
+fn error_statuses_are_not_parsed_as_data() {
+    let server = TestServer::start(|_, _| Response::status(404));
+    let err = fetch_with(&server.url, &FetchOptions::new()).unwrap_err();
+    assert!(err.to_string().contains("404"));
+}
+
+fn quick() -> FetchOptions {
+    FetchOptions::new().retries(2, Duration::from_millis(10))
+}
+
+#[test]
+fn server_errors_are_retried_with_backoff() {
+    let server = TestServer::start(|n, _| match n {
+        0 => Response::status(503),
+        1 => Response::status(429),
+        _ => Response::ok(BODY),
+    });
+    assert_eq!(fetch_with(&server.url, &quick()).unwrap(), BODY);
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[test]
+fn retries_give_up_with_an_http_error() {
+    let server = TestServer::start(|_, _| Response::status(500));
+    let err = fetch_with(&server.url, &quick()).unwrap_err();
+    assert!(matches!(err, FetchError::Http { status, .. } if status == 500));
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[test]
+fn client_errors_are_not_retried() {
+    let server = TestServer::start(|_, _| Response::status(403));
+    assert!(fetch_with(&server.url, &quick()).is_err());
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn unexpected_content_types_are_rejected() {
+    let server = TestServer::start(|_, _| {
+        Response::ok(b"<html>rate limited</html>")
+            .header("Content-Type", "text/html; charset=utf-8")
+    });
+    let err = fetch_with(&server.url, &quick()).unwrap_err();
+    assert!(matches!(err, FetchError::Invalid { .. }));
+    assert!(err
+        .to_string()
+        .ends_with("unexpected content type 'text/html'"));
+
+    let anything = quick().content_types(Vec::<String>::new());
+    assert!(fetch_with(&server.url, &anything).is_ok());
+}
+
+#[test]
+fn unreachable_hosts_are_network_errors() {
+    let options = FetchOptions::new()
+        .retries(1, Duration::from_millis(1))
+        .timeout(Duration::from_secs(2));
+    let err = fetch_with("http://127.0.0.1:9/ds_salaries.csv", &options).unwrap_err();
+    assert!(matches!(err, FetchError::Network { .. }));
+}
+
+#[test]
+fn slow_servers_time_out() {
+    let server = TestServer::start(|_, _| {
+        std::thread::sleep(Duration::from_millis(500));
+        Response::ok(BODY)
+    });
+    let options = FetchOptions::new()
+        .retries(0, Duration::ZERO)
+        .timeout(Duration::from_millis(100));
+    let err = fetch_with(&server.url, &options).unwrap_err();
+    match err {
+        FetchError::Network { source, .. } => assert!(source.is_timeout()),
+        other => panic!("expected a timeout, got {}", other),
+    }
+}
+
+#[test]
+fn records_separate_parse_failures() {
+    let server = TestServer::start(|_, _| Response::ok(b"work_year,salary\noops,1\n"));
+    let err = fetch_records(&server.url, &quick()).unwrap_err();
+    assert!(matches!(err, FetchError::Parse { .. }));
+}
+
+#[tokio::test]
+async fn async_front_end_shares_the_same_behaviour() {
+    let csv = include_bytes!("data/ds_salaries_sample.csv");
+    let server = TestServer::start(move |n, _| match n {
+        0 => Response::status(502),
+        _ => Response::ok(csv).header("Content-Type", "text/csv"),
+    });
+    let options = quick().cache_dir(temp_dir("async"));
+    let records = fetch_records_async(&server.url, &options).await.unwrap();
+    assert_eq!(records.len(), 12);
+
+    let offline = options.offline(true);
+    assert_eq!(fetch_async(&server.url, &offline).await.unwrap(), csv);
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn url_sources_stream_with_their_options() {
+    let server = TestServer::start(|_, _| Response::ok(BODY).header("Content-Type", "text/html"));
+    let mut body = Vec::new();
+    DataSource::url_with(&server.url, quick().content_types(["text/html"]))
+        .open()
+        .unwrap()
+        .read_to_end(&mut body)
+        .unwrap();
+    assert_eq!(body, BODY);
+
+    let Err(err) = DataSource::url_with(&server.url, quick()).open() else {
+        panic!("text/html accepted by default");
+    };
+    assert!(err
+        .to_string()
+        .ends_with("unexpected content type 'text/html'"));
+
+    let pinned = quick()
+        .content_types(["text/html"])
+        .sha256(sha256_hex(b"something else"));
+    assert!(matches!(
+        fetch_reader(&server.url, &pinned),
+        Err(FetchError::Invalid { .. })
+    ));
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use common::salaries;
+use rust_stats::dataset::load_dataset;
+use rust_stats::group::Agg;
+use rust_stats::table::{Column, Table, Value};
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+#[test]
+fn single_key_aggregations_form_a_tidy_table() {
+    let result = salaries()
+        .group_by(&["role"])
+        .unwrap()
+        .agg(&[
+            Agg::size(),
+            Agg::count("pay"),
+            Agg::mean("pay"),
+            Agg::median("pay"),
+            Agg::std("pay"),
+            Agg::quantile("pay", 0.25),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        result.column_names(),
+        [
+            "role",
+            "size",
+            "pay_count",
+            "pay_mean",
+            "pay_median",
+            "pay_std",
+            "pay_p25"
+        ]
+    );
+    // Groups are sorted with the missing key last.
+    assert_eq!(
+        result.column("role").unwrap(),
+        &Column::Str(vec![Some("ds".into()), Some("ml".into()), None])
+    );
+    assert_eq!(
+        result.column("size").unwrap(),
+        &Column::from(vec![3i64, 2, 1])
+    );
+    assert_eq!(
+        result.column("pay_count").unwrap(),
+        &Column::from(vec![3i64, 1, 1])
+    );
+    assert_eq!(
+        result.column("pay_mean").unwrap(),
+        &Column::Float(vec![Some(280.0 / 3.0), Some(80.0), Some(1.0)])
+    );
+    assert_eq!(result.row(0)[4], Value::Float(100.0));
+    let Value::Float(std) = result.row(0)[5] else {
+        panic!("std should be a float");
+    };
+    assert!((std - 30.550_504_633_038_93).abs() < 1e-9);
+    // A single value has no sample std.
+    assert_eq!(result.row(1)[5], Value::Null);
+    assert_eq!(result.row(0)[6], Value::Float(80.0));
+}
+
+#[test]
+fn multiple_keys_sort_numerically_within_each_key() {
+    let result = salaries()
+        .group_by(&["role", "year"])
+        .unwrap()
+        .agg(&[Agg::max("pay").alias("top_pay")])
+        .unwrap();
+
+    let rows: Vec<Vec<Value>> = (0..result.n_rows()).map(|i| result.row(i)).collect();
+    assert_eq!(
+        rows[..3],
+        [
+            vec![
+                Value::Str("ds".into()),
+                Value::Int(2022),
+                Value::Float(60.0)
+            ],
+            vec![
+                Value::Str("ds".into()),
+                Value::Int(2023),
+                Value::Float(120.0)
+            ],
+            vec![
+                Value::Str("ml".into()),
+                Value::Int(2022),
+                Value::Float(80.0)
+            ],
+        ]
+    );
+    assert_eq!(result.n_rows(), 5);
+}
+
+#[test]
+fn float_keys_group_by_value_not_by_text() {
+    let table = Table::from_columns(vec![(
+        "x",
+        Column::Float(vec![
+            Some(0.0),
+            Some(-0.0),
+            Some(f64::NAN),
+            None,
+            Some(f64::NAN),
+            Some(0.5),
+        ]),
+    )])
+    .unwrap();
+    let groups = table.group_by(&["x"]).unwrap();
+    assert_eq!(groups.indices(), [vec![0, 1], vec![5], vec![2, 4], vec![3]]);
+}
+
+#[test]
+fn salary_benchmarks_per_role_and_year() {
+    let table = Table::from_records(&load_dataset(SAMPLE).unwrap());
+    let groups = table.group_by(&["experience_level", "work_year"]).unwrap();
+    let result = groups
+        .agg(&[Agg::size(), Agg::median("salary_in_usd")])
+        .unwrap();
+
+    assert_eq!(result.n_rows(), groups.n_groups());
+    let total: i64 = result
+        .column("size")
+        .unwrap()
+        .f64_values()
+        .unwrap()
+        .iter()
+        .map(|&n| n as i64)
+        .sum();
+    assert_eq!(total, 12);
+}
+
+#[test]
+fn bad_keys_and_aggregations_are_errors() {
+    let table = salaries();
+    assert!(table.group_by(&["nope"]).is_err());
+    let groups = table.group_by(&["role"]).unwrap();
+    assert!(groups.agg(&[Agg::mean("role")]).is_err());
+    assert!(groups.agg(&[Agg::quantile("pay", 1.5)]).is_err());
+    assert!(groups.agg(&[Agg::mean("nope")]).is_err());
+}
+
+#[test]
+fn quantile_names_are_rounded_percentages() {
+    assert_eq!(Agg::quantile("pay", 0.07).name(), "pay_p7");
+    assert_eq!(Agg::quantile("pay", 0.125).name(), "pay_p12.5");
+    assert_eq!(Agg::quantile("pay", 0.999).name(), "pay_p99.9");
+    assert_eq!(Agg::quantile("pay", 1.0).name(), "pay_p100");
+    assert_eq!(Agg::quantile("pay", 0.0).name(), "pay_p0");
+}
@@ -0,0 +1,215 @@
+mod common;
+
+use common::{ints, strs, values};
+use rust_stats::dataset::load_dataset;
+use rust_stats::join::{DuplicateKeys, JoinKind, JoinOptions};
+use rust_stats::table::{Column, Table, Value};
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+fn people() -> Table {
+    Table::from_columns(vec![
+        (
+            "country",
+            strs(&[Some("US"), Some("GB"), Some("FR"), None, Some("US")]),
+        ),
+        ("year", Column::from(vec![2023i64, 2023, 2023, 2023, 2022])),
+        ("pay", Column::from(vec![1i64, 2, 3, 4, 5])),
+    ])
+    .unwrap()
+}
+
+fn countries() -> Table {
+    Table::from_columns(vec![
+        ("code", strs(&[Some("GB"), Some("US"), None])),
+        (
+            "name",
+            strs(&[Some("United Kingdom"), Some("United States"), Some("?")]),
+        ),
+    ])
+    .unwrap()
+}
+
+#[test]
+fn inner_left_and_anti_joins() {
+    let options = JoinOptions::new();
+    let inner = people()
+        .join_with(&countries(), &["country"], &["code"], &options)
+        .unwrap();
+    assert_eq!(inner.column_names(), ["country", "year", "pay", "name"]);
+    assert_eq!(values(&inner, "pay"), ints(&[1, 2, 5]));
+    assert_eq!(
+        values(&inner, "name")[1],
+        Value::Str("United Kingdom".into())
+    );
+
+    let left = people()
+        .join_with(
+            &countries(),
+            &["country"],
+            &["code"],
+            &options.clone().kind(JoinKind::Left),
+        )
+        .unwrap();
+    assert_eq!(values(&left, "pay"), ints(&[1, 2, 3, 4, 5]));
+    // Unmatched rows, including the missing key, get missing right columns.
+    assert_eq!(values(&left, "name")[2..4], [Value::Null, Value::Null]);
+
+    let anti = people()
+        .join_with(
+            &countries(),
+            &["country"],
+            &["code"],
+            &options.kind(JoinKind::Anti),
+        )
+        .unwrap();
+    assert_eq!(anti.column_names(), ["country", "year", "pay"]);
+    assert_eq!(values(&anti, "pay"), ints(&[3, 4]));
+}
+
+#[test]
+fn multi_key_join_with_duplicates() {
+    let cost = Table::from_columns(vec![
+        ("country", strs(&[Some("US"), Some("US"), Some("US")])),
+        ("year", Column::from(vec![2023i64, 2022, 2023])),
+        ("index", Column::from(vec![100i64, 95, 101])),
+    ])
+    .unwrap();
+
+    let all = people()
+        .join(&cost, &["country", "year"], JoinKind::Inner)
+        .unwrap();
+    assert_eq!(values(&all, "pay"), ints(&[1, 1, 5]));
+    assert_eq!(values(&all, "index"), ints(&[100, 101, 95]));
+
+    let first = people()
+        .join_with(
+            &cost,
+            &["country", "year"],
+            &["country", "year"],
+            &JoinOptions::new().duplicates(DuplicateKeys::First),
+        )
+        .unwrap();
+    assert_eq!(values(&first, "index"), ints(&[100, 95]));
+
+    let error = people()
+        .join_with(
+            &cost,
+            &["country", "year"],
+            &["country", "year"],
+            &JoinOptions::new().duplicates(DuplicateKeys::Error),
+        )
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "duplicate join key (US, 2023) in right table at rows 0 and 2"
+    );
+}
+
+#[test]
+fn clashing_names_get_a_suffix() {
+    let other = Table::from_columns(vec![
+        ("country", strs(&[Some("US")])),
+        ("pay", Column::from(vec![10i64])),
+    ])
+    .unwrap();
+    let joined = people()
+        .join(&other, &["country"], JoinKind::Inner)
+        .unwrap();
+    assert_eq!(
+        joined.column_names(),
+        ["country", "year", "pay", "pay_right"]
+    );
+    let joined = people()
+        .join_with(
+            &other,
+            &["country"],
+            &["country"],
+            &JoinOptions::new().suffix("_other"),
+        )
+        .unwrap();
+    assert_eq!(joined.column_names()[3], "pay_other");
+}
+
+#[test]
+fn float_keys_match_by_value_and_nan_never_matches() {
+    let left = Table::from_columns(vec![
+        (
+            "x",
+            Column::Float(vec![Some(0.0), Some(f64::NAN), Some(1.5)]),
+        ),
+        ("id", Column::from(vec![0i64, 1, 2])),
+    ])
+    .unwrap();
+    let right = Table::from_columns(vec![
+        (
+            "x",
+            Column::Float(vec![Some(-0.0), Some(f64::NAN), Some(f64::NAN), Some(1.5)]),
+        ),
+        (
+            "tag",
+            strs(&[Some("zero"), Some("nan"), Some("nan"), Some("one")]),
+        ),
+    ])
+    .unwrap();
+
+    // NaN keys are skipped, so they are not duplicates either.
+    let options = JoinOptions::new().duplicates(DuplicateKeys::Error);
+    let inner = left.join_with(&right, &["x"], &["x"], &options).unwrap();
+    assert_eq!(values(&inner, "id"), ints(&[0, 2]));
+    assert_eq!(
+        inner.column("tag").unwrap(),
+        &strs(&[Some("zero"), Some("one")])
+    );
+    let anti = left.join(&right, &["x"], JoinKind::Anti).unwrap();
+    assert_eq!(values(&anti, "id"), ints(&[1]));
+}
+
+#[test]
+fn bad_keys_are_errors() {
+    let people = people();
+    assert!(people
+        .join(&countries(), &["nope"], JoinKind::Inner)
+        .is_err());
+    assert!(people
+        .join_with(&countries(), &["country"], &[], &JoinOptions::new())
+        .is_err());
+    let error = people
+        .join_with(&countries(), &["year"], &["code"], &JoinOptions::new())
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "join key 'year' is int but 'code' is str"
+    );
+}
+
+#[test]
+fn enrich_salary_records() {
+    let salaries = Table::from_records(&load_dataset(SAMPLE).unwrap());
+    let regions = Table::from_columns(vec![
+        (
+            "code",
+            strs(&[Some("CA"), Some("US"), Some("GB"), Some("ES")]),
+        ),
+        (
+            "region",
+            strs(&[
+                Some("Americas"),
+                Some("Americas"),
+                Some("Europe"),
+                Some("Europe"),
+            ]),
+        ),
+    ])
+    .unwrap();
+    let enriched = salaries
+        .join_with(
+            &regions,
+            &["company_location"],
+            &["code"],
+            &JoinOptions::new().kind(JoinKind::Left),
+        )
+        .unwrap();
+    assert_eq!(enriched.n_rows(), salaries.n_rows());
+    assert_eq!(enriched.column("region").unwrap().null_count(), 0);
+}
@@ -0,0 +1,121 @@
+use rust_stats::dataset::{load_dataset, LoadMode, RowError};
+use rust_stats::descriptive::{calc_mean_with, MissingPolicy, RunningStats};
+use rust_stats::json::{
+    load_json, load_json_lines, load_json_lines_with, stream_json_lines, write_json,
+    write_json_lines,
+};
+use rust_stats::schema::{infer_schema, ColumnType, InferOptions, Schema};
+use rust_stats::table::{Column, Table};
+use serde_json::json;
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+#[test]
+fn records_round_trip_through_json() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let mut out = Vec::new();
+    write_json(&mut out, &dataset).unwrap();
+    assert_eq!(load_json(out.as_slice()).unwrap(), dataset);
+}
+
+#[test]
+fn records_round_trip_through_json_lines() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let mut out = Vec::new();
+    write_json_lines(&mut out, &dataset).unwrap();
+
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(text.lines().count(), dataset.len());
+    assert_eq!(load_json_lines(text.as_bytes()).unwrap(), dataset);
+}
+
+#[test]
+fn records_use_csv_codes_and_null_for_missing() {
+    let text = r#"{"work_year":2023,"experience_level":"SE","employment_type":"FT","job_title":"Data Scientist","salary":null,"salary_currency":null,"salary_in_usd":150000,"employee_residence":"US","remote_ratio":100,"company_location":"US","company_size":"M"}"#;
+    let records = load_json_lines(text.as_bytes()).unwrap();
+    assert_eq!(records[0].salary, None);
+    assert_eq!(records[0].salary_in_usd, Some(150000.0));
+
+    let value = serde_json::to_value(&records[0]).unwrap();
+    assert_eq!(value["experience_level"], "SE");
+    assert_eq!(value["salary"], serde_json::Value::Null);
+}
+
+#[test]
+fn bad_lines_are_row_errors_with_line_numbers() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let mut out = Vec::new();
+    write_json_lines(&mut out, &dataset[..2]).unwrap();
+    out.extend(b"\n{\"work_year\": \"soon\"}\n");
+    write_json_lines(&mut out, &dataset[2..3]).unwrap();
+
+    let results: Vec<_> = stream_json_lines(out.as_slice()).collect();
+    assert_eq!(results.len(), 4);
+    let error = results[2]
+        .as_ref()
+        .unwrap_err()
+        .downcast_ref::<RowError>()
+        .unwrap();
+    assert_eq!(error.line, 4);
+    assert!(!error.message.contains("at line"));
+
+    let loaded = load_json_lines_with(out.as_slice(), LoadMode::Collect).unwrap();
+    assert_eq!(loaded.records.len(), 3);
+    assert_eq!(loaded.report.rejected[0].line, 4);
+    assert!(load_json_lines(out.as_slice()).is_err());
+}
+
+#[test]
+fn computed_results_and_tables_serialize() {
+    let stat = calc_mean_with(&[Some(1.0), None, Some(3.0)], MissingPolicy::Skip).unwrap();
+    let mut out = Vec::new();
+    write_json(&mut out, &stat).unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(value, json!({"value": 2.0, "excluded": 1}));
+
+    let table = Table::from_columns(vec![
+        ("year", Column::Int(vec![Some(2022), Some(2023)])),
+        ("title", Column::Str(vec![Some("DS".into()), None])),
+    ])
+    .unwrap();
+    assert_eq!(
+        serde_json::to_value(&table).unwrap(),
+        json!([{"year": 2022, "title": "DS"}, {"year": 2023, "title": null}])
+    );
+}
+
+#[test]
+fn running_stats_and_schemas_round_trip() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let mut stats = RunningStats::new();
+    stats.extend(dataset.iter().filter_map(|record| record.salary_in_usd));
+    stats.push_option(None);
+    let mut out = Vec::new();
+    write_json(&mut out, &stats).unwrap();
+    let back: RunningStats = serde_json::from_slice(&out).unwrap();
+    assert_eq!(back, stats);
+    assert_eq!(back.range().unwrap(), stats.range().unwrap());
+
+    // An empty accumulator has no range yet and still round-trips.
+    let empty = serde_json::to_string(&RunningStats::new()).unwrap();
+    let empty: RunningStats = serde_json::from_str(&empty).unwrap();
+    assert!(empty.range().is_err());
+
+    let schema = infer_schema(SAMPLE.as_bytes(), &InferOptions::new()).unwrap();
+    let mut out = Vec::new();
+    write_json_lines(&mut out, &schema.columns).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(text.lines().count(), schema.columns.len());
+    let columns = text
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(Schema { columns }, schema);
+
+    let work_year = serde_json::to_value(schema.column("work_year").unwrap()).unwrap();
+    assert_eq!(work_year["column_type"], json!("int"));
+    assert_eq!(
+        serde_json::from_value::<ColumnType>(json!("categorical")).unwrap(),
+        ColumnType::Categorical
+    );
+}
@@ -0,0 +1,112 @@
+use rust_stats::dataset::load_dataset;
+use rust_stats::descriptive::{
+    calc_mean_with, calc_median_with, calc_std_with, range_with, MissingPolicy, RunningStats,
+};
+use rust_stats::features::standardize_salary;
+
+const COLUMN: [Option<f32>; 6] = [Some(1.0), None, Some(3.0), Some(f32::NAN), Some(5.0), None];
+
+#[test]
+fn skip_excludes_and_counts_missing_values() {
+    let mean = calc_mean_with(&COLUMN, MissingPolicy::Skip).unwrap();
+    assert_eq!(mean.value, 3.0);
+    assert_eq!(mean.excluded, 3);
+    assert_eq!(
+        calc_median_with(&COLUMN, MissingPolicy::Skip)
+            .unwrap()
+            .value,
+        3.0
+    );
+    assert_eq!(
+        calc_std_with(&COLUMN, MissingPolicy::Skip).unwrap().value,
+        2.0
+    );
+    assert_eq!(range_with(&COLUMN, MissingPolicy::Skip).unwrap().value, 4.0);
+}
+
+#[test]
+fn propagate_returns_nan() {
+    let mean = calc_mean_with(&COLUMN, MissingPolicy::Propagate).unwrap();
+    assert!(mean.value.is_nan());
+    assert_eq!(mean.excluded, 0);
+    assert!(range_with(&COLUMN, MissingPolicy::Propagate)
+        .unwrap()
+        .value
+        .is_nan());
+}
+
+#[test]
+fn error_policy_rejects_missing_values() {
+    let err = calc_std_with(&COLUMN, MissingPolicy::Error).unwrap_err();
+    assert_eq!(err.to_string(), "column has 3 missing values");
+    let complete = [Some(2.0), Some(4.0)];
+    let mean = calc_mean_with(&complete, MissingPolicy::Error).unwrap();
+    assert_eq!((mean.value, mean.excluded), (3.0, 0));
+}
+
+#[test]
+fn all_missing_column_is_an_error_even_when_skipping() {
+    assert!(calc_mean_with(&[None::<f32>, None], MissingPolicy::Skip).is_err());
+}
+
+#[test]
+fn running_stats_count_missing_values() {
+    let stats: RunningStats = COLUMN.iter().copied().collect();
+    assert_eq!(stats.count(), 3);
+    assert_eq!(stats.missing(), 3);
+    assert_eq!(stats.mean_with(MissingPolicy::Skip).unwrap().excluded, 3);
+    assert!(stats
+        .std_with(MissingPolicy::Propagate)
+        .unwrap()
+        .value
+        .is_nan());
+    assert!(stats.range_with(MissingPolicy::Error).is_err());
+}
+
+#[test]
+fn missing_salaries_stay_missing_after_standardizing() {
+    let csv = "work_year,experience_level,employment_type,job_title,salary,salary_currency,salary_in_usd,employee_residence,remote_ratio,company_location,company_size\n\
+               2023,SE,FT,Data Scientist,,,100,,,,\n\
+               2023,SE,FT,Data Scientist,,,,,,,\n\
+               2023,SE,FT,Data Scientist,,,300,,,,\n";
+    let z = standardize_salary(&load_dataset(csv).unwrap()).unwrap();
+    assert_eq!(
+        z,
+        vec![Some(-1.0 / 2f32.sqrt()), None, Some(1.0 / 2f32.sqrt())]
+    );
+}
+
+#[test]
+fn nan_gives_nan_unless_skipped() {
+    use rust_stats::descriptive::{calc_mean, calc_median, calc_std, range};
+    let values = [1.0_f32, f32::NAN, 2.0];
+    assert!(calc_mean(&values).unwrap().is_nan());
+    assert!(calc_median(&values).unwrap().is_nan());
+    assert!(calc_std(&values).unwrap().is_nan());
+    assert!(range(&values).unwrap().is_nan());
+    assert!(range(&[-f64::NAN, 1.0]).unwrap().is_nan());
+
+    let column = values.map(Some);
+    assert_eq!(
+        calc_median_with(&column, MissingPolicy::Skip)
+            .unwrap()
+            .value,
+        1.5
+    );
+}
+
+#[test]
+fn with_variants_take_any_numeric_column() {
+    let years = [Some(2020_i64), None, Some(2023)];
+    let mean = calc_mean_with(&years, MissingPolicy::Skip).unwrap();
+    assert_eq!((mean.value, mean.excluded), (2021.5_f64, 1));
+    assert_eq!(range_with(&years, MissingPolicy::Skip).unwrap().value, 3.0);
+
+    let pay = [Some(1.0_f64), Some(f64::NAN), Some(4.0)];
+    let median = calc_median_with(&pay, MissingPolicy::Skip).unwrap();
+    assert_eq!((median.value, median.excluded), (2.5, 1));
+    assert!(calc_std_with(&pay, MissingPolicy::Propagate)
+        .unwrap()
+        .value
+        .is_nan());
+}
@@ -0,0 +1,138 @@
+mod common;
+
+use common::{ints, salaries, strs, values};
+use rust_stats::dataset::load_dataset;
+use rust_stats::group::Agg;
+use rust_stats::pivot::{Normalize, PivotOptions};
+use rust_stats::table::{Column, Table, Value};
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+#[test]
+fn crosstab_counts_with_margins() {
+    let counts = salaries()
+        .pivot_with(
+            "role",
+            "year",
+            &Agg::size(),
+            &PivotOptions::new().margins(true),
+        )
+        .unwrap();
+
+    assert_eq!(counts.column_names(), ["role", "2022", "2023", "All"]);
+    // The row with a missing role is left out.
+    assert_eq!(
+        counts.column("role").unwrap(),
+        &strs(&[Some("ds"), Some("ml"), Some("All")])
+    );
+    assert_eq!(values(&counts, "2022"), ints(&[1, 1, 2]));
+    assert_eq!(values(&counts, "2023"), ints(&[2, 1, 3]));
+    assert_eq!(values(&counts, "All"), ints(&[3, 2, 5]));
+    assert_eq!(
+        salaries().crosstab("role", "year").unwrap().column_names(),
+        ["role", "2022", "2023"]
+    );
+}
+
+#[test]
+fn median_pivot_with_empty_cells() {
+    let medians = salaries()
+        .pivot_with(
+            "role",
+            "year",
+            &Agg::median("pay"),
+            &PivotOptions::new().margins(true).margins_name("Total"),
+        )
+        .unwrap();
+
+    assert_eq!(
+        medians.column("2022").unwrap(),
+        &Column::Float(vec![Some(60.0), Some(80.0), Some(70.0)])
+    );
+    // "ml" in 2023 has only a missing salary.
+    assert_eq!(
+        medians.column("2023").unwrap(),
+        &Column::Float(vec![Some(110.0), None, Some(110.0)])
+    );
+    // Margins aggregate the underlying rows, not the cells.
+    assert_eq!(
+        medians.column("Total").unwrap(),
+        &Column::Float(vec![Some(100.0), Some(80.0), Some(90.0)])
+    );
+}
+
+#[test]
+fn normalized_shares() {
+    let table = salaries();
+    let options = PivotOptions::new().margins(true);
+    let share = |normalize| {
+        table
+            .pivot_with(
+                "role",
+                "year",
+                &Agg::size(),
+                &options.clone().normalize(normalize),
+            )
+            .unwrap()
+    };
+
+    let rows = share(Normalize::Rows);
+    assert_eq!(
+        rows.column("2023").unwrap(),
+        &Column::Float(vec![Some(2.0 / 3.0), Some(0.5), Some(0.6)])
+    );
+    assert_eq!(
+        rows.column("All").unwrap(),
+        &Column::Float(vec![Some(1.0); 3])
+    );
+
+    let columns = share(Normalize::Columns);
+    assert_eq!(
+        columns.column("2022").unwrap(),
+        &Column::Float(vec![Some(0.5), Some(0.5), Some(1.0)])
+    );
+
+    let all = share(Normalize::All);
+    assert_eq!(
+        all.column("2023").unwrap(),
+        &Column::Float(vec![Some(0.4), Some(0.2), Some(0.6)])
+    );
+
+    assert!(table
+        .pivot_with(
+            "role",
+            "year",
+            &Agg::median("pay"),
+            &PivotOptions::new().normalize(Normalize::Rows),
+        )
+        .is_err());
+}
+
+#[test]
+fn salary_grid_prints_and_exports() {
+    let salaries = Table::from_records(&load_dataset(SAMPLE).unwrap());
+    let grid = salaries
+        .pivot_with(
+            "remote_ratio",
+            "company_size",
+            &Agg::size(),
+            &PivotOptions::new().margins(true),
+        )
+        .unwrap();
+
+    let total = grid.row(grid.n_rows() - 1);
+    assert_eq!(total.last().unwrap(), &Value::Int(12));
+
+    let printed = grid.to_string();
+    assert!(printed.starts_with("remote_ratio"));
+    assert_eq!(printed.lines().count(), grid.n_rows() + 1);
+
+    let mut csv = Vec::new();
+    grid.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert_eq!(csv.lines().count(), grid.n_rows() + 1);
+    assert!(csv.lines().last().unwrap().starts_with("All,"));
+    assert!(salaries
+        .pivot("nope", "company_size", &Agg::size())
+        .is_err());
+}
@@ -0,0 +1,11 @@
+use rust_stats::plot::plot_histogram;
+
+#[test]
+fn histogram_is_written_to_disk() {
+    let path = std::env::temp_dir().join("rust_stats_histogram_test.html");
+    let path = path.to_str().unwrap();
+    plot_histogram(&[1.0, 2.0, 2.0, 3.0], 3, "Test", path);
+    let html = std::fs::read_to_string(path).unwrap();
+    assert!(html.contains("histogram"));
+    std::fs::remove_file(path).unwrap();
+}
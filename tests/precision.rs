@@ -0,0 +1,119 @@
+//! Mean and standard deviation against references computed with exact
+//! rational arithmetic (and 50-digit square roots), rounded to the output
+//! type.
+
+use rust_stats::descriptive::{calc_mean, calc_std, NeumaierSum, RunningStats};
+use rust_stats::group::Agg;
+use rust_stats::table::{Column, Table};
+use std::iter::repeat_n;
+
+/// `salary_in_usd` of the sample rows.
+const SALARIES: [f32; 12] = [
+    85847.0, 30000.0, 25500.0, 175000.0, 120000.0, 222200.0, 136000.0, 219000.0, 141000.0,
+    147100.0, 49253.0, 230000.0,
+];
+
+fn relative_error(value: f64, reference: f64) -> f64 {
+    ((value - reference) / reference).abs()
+}
+
+/// 1.2 million salaries: summing them in `f32` is off by thousands.
+fn many_salaries() -> impl Iterator<Item = f32> + Clone {
+    SALARIES.iter().copied().cycle().take(1_200_000)
+}
+
+#[test]
+fn f32_salaries_round_to_the_exact_mean_and_std() {
+    // 131741.666..., 69731.457039026794908827...
+    assert_eq!(calc_mean(many_salaries()).unwrap(), 131_741.67_f32);
+    assert_eq!(calc_std(many_salaries()).unwrap(), 69_731.46_f32);
+
+    let stats: RunningStats = many_salaries().collect();
+    assert_eq!(stats.mean().unwrap(), 131_741.67_f32);
+    assert_eq!(stats.std().unwrap(), 69_731.46_f32);
+}
+
+#[test]
+fn compensated_mean_of_repeated_tenths() {
+    // The exact mean of a million copies of 0.1_f64 is 0.1_f64 itself;
+    // plain summation ends at 0.10000000000133288.
+    assert_eq!(calc_mean(repeat_n(0.1_f64, 1_000_000)).unwrap(), 0.1);
+}
+
+#[test]
+fn cancellation_does_not_lose_small_terms() {
+    assert_eq!(calc_mean([1.0, 1e100, 1.0, -1e100]).unwrap(), 0.5);
+    let sum: NeumaierSum = [1e16, 1.0, -1e16, 1.0].into_iter().sum();
+    assert_eq!(sum.value(), 2.0);
+}
+
+#[test]
+fn variance_survives_a_large_offset() {
+    // Sample variance 30 at any offset; the textbook sum-of-squares
+    // formula breaks down here.
+    for offset in [0.0, 1e6, 1e9, 1e12] {
+        let values = [4.0, 7.0, 13.0, 16.0].map(|v| offset + v);
+        let std: f64 = calc_std(values).unwrap();
+        assert!(
+            relative_error(std, 30f64.sqrt()) < 1e-9,
+            "offset {}",
+            offset
+        );
+    }
+    let close: f64 = calc_std([1e9 + 4.0, 1e9 + 7.0, 1e9 + 13.0, 1e9 + 16.0]).unwrap();
+    assert!(relative_error(close, 5.477_225_575_051_661) < 1e-15);
+}
+
+#[test]
+fn generated_f64_column() {
+    let values = (0..200_000u64).map(|i| 250_000.0 + ((i * 7919) % 10007) as f64 * 0.37);
+
+    // 251851.12071149999999892315827...
+    let mean: f64 = calc_mean(values.clone()).unwrap();
+    assert!(relative_error(mean, 251_851.120_711_5) < 1e-15);
+    // 1068.8482669435496248658039977...
+    let std: f64 = calc_std(values).unwrap();
+    assert!(relative_error(std, 1_068.848_266_943_549_6) < 1e-12);
+}
+
+#[test]
+fn integer_columns_are_exact() {
+    let years = repeat_n(2023_i64, 999_999).chain([2020]);
+    // (2023 * 999_999 + 2020) / 1_000_000
+    assert_eq!(calc_mean(years).unwrap(), 2_022.999_997);
+    let std: f64 = calc_std(repeat_n(2023_i64, 999_999).chain([2020])).unwrap();
+    // sqrt(9 * 999_999 / 1_000_000 / 999_999)
+    assert!(relative_error(std, 0.003) < 1e-9);
+}
+
+#[test]
+fn infinities_survive_compensation() {
+    assert_eq!(calc_mean([f64::INFINITY, 1.0]).unwrap(), f64::INFINITY);
+    assert_eq!(
+        calc_mean([1.0, f64::NEG_INFINITY]).unwrap(),
+        f64::NEG_INFINITY
+    );
+    let overflow: NeumaierSum = [f64::MAX, f64::MAX, 1.0].into_iter().sum();
+    assert_eq!(overflow.value(), f64::INFINITY);
+    assert!(calc_mean([f64::INFINITY, f64::NEG_INFINITY])
+        .unwrap()
+        .is_nan());
+
+    let table = Table::from_columns(vec![
+        ("key", Column::from(vec![1i64, 1, 2])),
+        (
+            "pay",
+            Column::Float(vec![Some(f64::INFINITY), Some(1.0), Some(2.0)]),
+        ),
+    ])
+    .unwrap();
+    let sums = table
+        .group_by(&["key"])
+        .unwrap()
+        .agg(&[Agg::sum("pay")])
+        .unwrap();
+    assert_eq!(
+        sums.column("pay_sum").unwrap(),
+        &Column::Float(vec![Some(f64::INFINITY), Some(2.0)])
+    );
+}
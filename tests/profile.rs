@@ -0,0 +1,103 @@
+use rust_stats::dataset::load_dataset;
+use rust_stats::profile::{profile, profile_table_with, ColumnStats, ProfileOptions};
+use rust_stats::table::{Column, Table};
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+fn assert_close(actual: Option<f64>, expected: f64) {
+    let actual = actual.unwrap();
+    assert!(
+        (actual - expected).abs() < 1e-9 * expected.abs().max(1.0),
+        "{} != {}",
+        actual,
+        expected
+    );
+}
+
+fn numbers() -> Table {
+    Table::from_columns(vec![
+        (
+            "x",
+            Column::Float(vec![Some(0.0), Some(1.0), Some(2.0), Some(10.0), None]),
+        ),
+        (
+            "c",
+            Column::Str(vec![
+                Some("a".into()),
+                Some("b".into()),
+                Some("a".into()),
+                None,
+                Some("b".into()),
+            ]),
+        ),
+    ])
+    .unwrap()
+}
+
+#[test]
+fn numeric_columns_match_pandas_describe() {
+    let profile = profile_table_with(&numbers(), &ProfileOptions::new()).unwrap();
+    let ColumnStats::Numeric(x) = &profile.column("x").unwrap().stats else {
+        panic!("x should be numeric");
+    };
+    // pandas: pd.Series([0, 1, 2, 10]).describe() and .skew()
+    assert_eq!((x.count, x.missing, x.zeros), (4, 1, 1));
+    assert_close(x.mean, 3.25);
+    assert_close(x.std, 4.573_474_244_670_748);
+    assert_close(x.min, 0.0);
+    assert_close(x.q25, 0.75);
+    assert_close(x.median, 1.5);
+    assert_close(x.q75, 4.0);
+    assert_close(x.max, 10.0);
+    assert_close(x.skew, 1.811_067_053_793_524);
+}
+
+#[test]
+fn categorical_columns_get_frequencies_and_entropy() {
+    let profile = profile_table_with(&numbers(), &ProfileOptions::new().top_k(1)).unwrap();
+    let ColumnStats::Categorical(c) = &profile.column("c").unwrap().stats else {
+        panic!("c should be categorical");
+    };
+    assert_eq!((c.count, c.missing, c.distinct), (4, 1, 2));
+    // a and b tie, so the alphabetically first one wins.
+    assert_eq!(c.top, [("a".to_string(), 2)]);
+    assert!((c.entropy - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn small_columns_leave_undefined_statistics_empty() {
+    let table = Table::from_columns(vec![("x", Column::Int(vec![Some(5), None]))]).unwrap();
+    let profile = profile_table_with(&table, &ProfileOptions::new()).unwrap();
+    let ColumnStats::Numeric(x) = &profile.columns[0].stats else {
+        panic!("x should be numeric");
+    };
+    assert_close(x.mean, 5.0);
+    assert_eq!((x.std, x.skew), (None, None));
+}
+
+#[test]
+fn dataset_profile_covers_every_field() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let profile = profile(&dataset).unwrap();
+    assert_eq!(profile.rows, dataset.len());
+    assert_eq!(profile.columns.len(), 11);
+    assert!(matches!(
+        profile.column("salary_in_usd").unwrap().stats,
+        ColumnStats::Numeric(_)
+    ));
+    assert!(matches!(
+        profile.column("experience_level").unwrap().stats,
+        ColumnStats::Categorical(_)
+    ));
+
+    let text = profile.to_string();
+    assert!(text.starts_with("12 rows"));
+    assert!(text.contains("salary_in_usd"));
+    assert!(text.contains("entropy"));
+
+    let json = serde_json::to_value(&profile).unwrap();
+    assert_eq!(json["columns"][0]["name"], "work_year");
+    assert_eq!(json["columns"][0]["kind"], "numeric");
+    assert_eq!(json["columns"][1]["kind"], "categorical");
+    assert!(json["columns"][1]["top"][0][1].is_u64());
+}
@@ -0,0 +1,115 @@
+use rust_stats::categories::ExperienceLevel;
+use rust_stats::dataset::{load_dataset, stream_records, SalaryRecord};
+use rust_stats::sample::{
+    reservoir_sample, sample_indices, sample_records, stratified_indices, stratified_sample,
+    stratified_train_test_split, train_test_split,
+};
+use rust_stats::table::{Column, Table, Value};
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+#[test]
+fn samples_are_seeded_sorted_and_distinct() {
+    let a = sample_indices(100, 10, 7).unwrap();
+    assert_eq!(a, sample_indices(100, 10, 7).unwrap());
+    assert_ne!(a, sample_indices(100, 10, 8).unwrap());
+    assert_eq!(a.len(), 10);
+    assert!(a.windows(2).all(|w| w[0] < w[1]));
+    assert!(a.iter().all(|&i| i < 100));
+
+    assert_eq!(sample_indices(5, 5, 0).unwrap(), [0, 1, 2, 3, 4]);
+    assert!(sample_indices(5, 6, 0).is_err());
+}
+
+#[test]
+fn stratified_indices_keep_proportions() {
+    let keys: Vec<&str> = std::iter::repeat_n("a", 80)
+        .chain(std::iter::repeat_n("b", 20))
+        .collect();
+    let picked = stratified_indices(&keys, 0.25, 3).unwrap();
+    assert_eq!(picked.iter().filter(|&&i| keys[i] == "a").count(), 20);
+    assert_eq!(picked.iter().filter(|&&i| keys[i] == "b").count(), 5);
+    assert!(stratified_indices(&keys, 1.5, 3).is_err());
+}
+
+#[test]
+fn record_samples() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let sample = sample_records(&dataset, 4, 1).unwrap();
+    assert_eq!(sample.len(), 4);
+    assert!(sample.iter().all(|r| dataset.contains(r)));
+
+    let by_level = stratified_sample(&dataset, |r| r.experience_level, 1.0, 1).unwrap();
+    assert_eq!(by_level, dataset);
+    let none = stratified_sample(&dataset, |r| r.experience_level, 0.0, 1).unwrap();
+    assert!(none.is_empty());
+}
+
+#[test]
+fn reservoir_sample_of_a_stream() {
+    let sample = reservoir_sample((0..1000).map(Ok), 10, 42).unwrap();
+    assert_eq!(sample.len(), 10);
+    assert!(sample.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(sample, reservoir_sample((0..1000).map(Ok), 10, 42).unwrap());
+
+    let short = reservoir_sample((0..3).map(Ok), 10, 42).unwrap();
+    assert_eq!(short, [0, 1, 2]);
+
+    let records = reservoir_sample(stream_records(SAMPLE.as_bytes()), 5, 9).unwrap();
+    assert_eq!(records.len(), 5);
+
+    let failing = [Ok(1), Err(anyhow::anyhow!("bad row")), Ok(3)];
+    assert!(reservoir_sample(failing, 2, 0).is_err());
+}
+
+#[test]
+fn train_test_split_partitions_the_dataset() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let split = train_test_split(&dataset, 0.25, 5).unwrap();
+    assert_eq!(split.test.len(), 3);
+    assert_eq!(split.train.len(), 9);
+    assert!(split.test.iter().all(|r| !split.train.contains(r)));
+
+    let split = stratified_train_test_split(&dataset, |r| r.experience_level, 0.5, 5).unwrap();
+    for level in [ExperienceLevel::Senior, ExperienceLevel::Executive] {
+        let count = |records: &[SalaryRecord]| {
+            records
+                .iter()
+                .filter(|r| r.experience_level == level)
+                .count()
+        };
+        let total = count(&dataset);
+        assert_eq!(count(&split.test), (total as f64 * 0.5).round() as usize);
+        assert_eq!(count(&split.train) + count(&split.test), total);
+    }
+}
+
+#[test]
+fn table_samples() {
+    let table = Table::from_columns(vec![
+        (
+            "level",
+            Column::Str(
+                ["a", "a", "a", "a", "b", "b"]
+                    .iter()
+                    .map(|s| Some(s.to_string()))
+                    .chain([None, None])
+                    .collect(),
+            ),
+        ),
+        ("id", Column::from(vec![0i64, 1, 2, 3, 4, 5, 6, 7])),
+    ])
+    .unwrap();
+
+    assert_eq!(table.sample(3, 11).unwrap().n_rows(), 3);
+    assert!(table.sample(9, 11).is_err());
+
+    let half = table.stratified_sample("level", 0.5, 11).unwrap();
+    assert_eq!(half.n_rows(), 4);
+    let levels = half.column("level").unwrap();
+    let nulls = (0..levels.len())
+        .filter(|&i| levels.get(i) == Value::Null)
+        .count();
+    assert_eq!(nulls, 1);
+    assert!(table.stratified_sample("nope", 0.5, 11).is_err());
+}
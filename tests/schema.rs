@@ -0,0 +1,114 @@
+use rust_stats::schema::{infer_schema, ColumnType, InferOptions};
+use rust_stats::table::{Column, DataType, Table};
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+#[test]
+fn salary_columns_are_inferred() {
+    let schema = infer_schema(SAMPLE.as_bytes(), &InferOptions::default()).unwrap();
+    let types: Vec<ColumnType> = schema.columns.iter().map(|c| c.column_type).collect();
+    use ColumnType::*;
+    assert_eq!(
+        types,
+        vec![
+            Int,
+            Categorical,
+            Categorical,
+            Categorical,
+            Int,
+            Categorical,
+            Int,
+            Categorical,
+            Int,
+            Categorical,
+            Categorical
+        ]
+    );
+    assert!(schema.columns.iter().all(|c| !c.nullable));
+}
+
+#[test]
+fn dates_bools_text_and_nullability_are_detected() {
+    let csv = "day,flag,comment,score\n\
+               2023-01-05,true,great team,1.5\n\
+               2023/02/10,False,long hours,\n\
+               2023-03-15,TRUE,remote friendly,2\n";
+    let schema = infer_schema(csv.as_bytes(), &InferOptions::default()).unwrap();
+    let column = |name| schema.column(name).unwrap();
+    assert_eq!(column("day").column_type, ColumnType::Date);
+    assert_eq!(column("flag").column_type, ColumnType::Bool);
+    assert_eq!(column("comment").column_type, ColumnType::Text);
+    assert_eq!(column("score").column_type, ColumnType::Float);
+    assert!(column("score").nullable);
+    assert!(!column("day").nullable);
+
+    let table = Table::from_reader(csv.as_bytes()).unwrap();
+    assert_eq!(table.column("day").unwrap().dtype(), DataType::Date);
+    assert_eq!(table.column("score").unwrap().null_count(), 1);
+}
+
+#[test]
+fn ambiguous_columns_are_reported() {
+    let mut csv = String::from("remote,amount\n");
+    for i in 0..19 {
+        csv.push_str(&format!("{},{}\n", i % 2, i * 100));
+    }
+    csv.push_str("1,unknown\n");
+    let schema = infer_schema(csv.as_bytes(), &InferOptions::default()).unwrap();
+
+    let remote = schema.column("remote").unwrap();
+    assert_eq!(remote.column_type, ColumnType::Int);
+    assert!(remote.ambiguity.as_ref().unwrap().contains("bool"));
+
+    let amount = schema.column("amount").unwrap();
+    assert_eq!(amount.column_type, ColumnType::Text);
+    assert_eq!(
+        amount.ambiguity.as_deref(),
+        Some("19 of 20 values parse as int")
+    );
+    assert_eq!(schema.ambiguous().count(), 2);
+}
+
+#[test]
+fn overrides_pin_column_types() {
+    let options = InferOptions::new().override_type("remote_ratio", ColumnType::Float);
+    let schema = infer_schema(SAMPLE.as_bytes(), &options).unwrap();
+    let remote = schema.column("remote_ratio").unwrap();
+    assert_eq!(remote.column_type, ColumnType::Float);
+    assert!(remote.overridden);
+
+    let table = Table::from_reader_with(SAMPLE.as_bytes(), &options).unwrap();
+    assert!(matches!(
+        table.column("remote_ratio").unwrap(),
+        Column::Float(_)
+    ));
+
+    let unknown = InferOptions::new().override_type("nope", ColumnType::Int);
+    assert!(infer_schema(SAMPLE.as_bytes(), &unknown).is_err());
+}
+
+#[test]
+fn in_memory_reads_infer_from_every_row() {
+    let csv = "n\n1\n2\n3\n4.5\n";
+    let options = InferOptions::new().sample_rows(3);
+    let table = Table::from_reader_with(csv.as_bytes(), &options).unwrap();
+    assert!(matches!(table.column("n").unwrap(), Column::Float(_)));
+
+    // Streaming inference stops at the sample, so a pinned schema built
+    // from it rejects the late row.
+    let schema = infer_schema(csv.as_bytes(), &options).unwrap();
+    assert_eq!(schema.columns[0].column_type, ColumnType::Int);
+    let err = Table::from_reader_with_schema(csv.as_bytes(), &schema).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "line 5, column 'n': '4.5' is not a valid int"
+    );
+}
+
+#[test]
+fn schema_prints_as_a_table() {
+    let schema = infer_schema("a,b\n1,\n2,x\n".as_bytes(), &InferOptions::default()).unwrap();
+    let text = schema.to_string();
+    assert!(text.starts_with("column  type"));
+    assert!(text.contains("b       text         yes"));
+}
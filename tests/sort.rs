@@ -0,0 +1,107 @@
+mod common;
+
+use common::{ints, salaries, values};
+use rust_stats::dataset::load_dataset;
+use rust_stats::group::Agg;
+use rust_stats::sort::{RankMethod, SortKey};
+use rust_stats::table::{Column, Table};
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+#[test]
+fn sort_is_stable_and_places_missing_values() {
+    let table = salaries();
+
+    let ascending = table.sort_by(&[SortKey::asc("bonus")]).unwrap();
+    assert_eq!(values(&ascending, "id"), ints(&[3, 0, 5, 2, 1, 4]));
+
+    // Missing values stay last when descending, unless asked otherwise.
+    let descending = table.sort_by(&[SortKey::desc("bonus")]).unwrap();
+    assert_eq!(values(&descending, "id"), ints(&[2, 0, 5, 3, 1, 4]));
+    let first = table
+        .sort_by(&[SortKey::desc("bonus").nulls_first()])
+        .unwrap();
+    assert_eq!(values(&first, "id"), ints(&[1, 4, 2, 0, 5, 3]));
+}
+
+#[test]
+fn later_keys_break_ties() {
+    let sorted = salaries()
+        .sort_by(&[SortKey::asc("country"), SortKey::desc("bonus")])
+        .unwrap();
+    assert_eq!(values(&sorted, "id"), ints(&[3, 1, 2, 0, 5, 4]));
+    assert!(salaries().sort_by(&[SortKey::asc("nope")]).is_err());
+}
+
+#[test]
+fn top_k_matches_a_full_sort() {
+    let table = salaries();
+    let keys = [SortKey::desc("bonus")];
+    let full = table.sort_by(&keys).unwrap();
+    for k in 0..=8 {
+        let top = table.top_k(k, &keys).unwrap();
+        assert_eq!(values(&top, "id"), values(&full, "id")[..k.min(6)]);
+    }
+}
+
+#[test]
+fn ranks_over_the_whole_table() {
+    let table = salaries();
+    let key = SortKey::desc("bonus");
+
+    let min = table.rank(&key, RankMethod::Min, &[]).unwrap();
+    assert_eq!(
+        min,
+        Column::Int(vec![Some(2), None, Some(1), Some(4), None, Some(2)])
+    );
+    let dense = table.rank(&key, RankMethod::Dense, &[]).unwrap();
+    assert_eq!(
+        dense,
+        Column::Int(vec![Some(2), None, Some(1), Some(3), None, Some(2)])
+    );
+    let percent = table.rank(&key, RankMethod::Percent, &[]).unwrap();
+    assert_eq!(
+        percent,
+        Column::Float(vec![
+            Some(0.5),
+            None,
+            Some(0.25),
+            Some(1.0),
+            None,
+            Some(0.5)
+        ])
+    );
+}
+
+#[test]
+fn ranks_within_groups() {
+    let mut table = salaries();
+    let rank = table
+        .rank(&SortKey::desc("bonus"), RankMethod::Min, &["country"])
+        .unwrap();
+    assert_eq!(
+        rank,
+        Column::Int(vec![Some(2), None, Some(1), Some(1), None, Some(2)])
+    );
+    let n_cols = table.n_cols();
+    table.add_column("bonus_rank", rank).unwrap();
+    assert_eq!(table.n_cols(), n_cols + 1);
+}
+
+#[test]
+fn highest_paid_levels_by_median() {
+    let table = Table::from_records(&load_dataset(SAMPLE).unwrap());
+    let medians = table
+        .group_by(&["experience_level"])
+        .unwrap()
+        .agg(&[Agg::median("salary_in_usd")])
+        .unwrap();
+    let keys = [SortKey::desc("salary_in_usd_median")];
+
+    let top = medians.top_k(2, &keys).unwrap();
+    assert_eq!(top.n_rows(), 2);
+    assert_eq!(
+        values(&top, "experience_level"),
+        values(&medians.sort_by(&keys).unwrap(), "experience_level")[..2]
+    );
+}
@@ -0,0 +1,23 @@
+use rust_stats::dataset::{load_dataset, load_source};
+use rust_stats::source::DataSource;
+use std::io::Cursor;
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+#[test]
+fn path_source_matches_in_memory_parse() {
+    let dataset = load_source(DataSource::path("tests/data/ds_salaries_sample.csv")).unwrap();
+    assert_eq!(dataset, load_dataset(SAMPLE).unwrap());
+}
+
+#[test]
+fn reader_source_accepts_any_read() {
+    let dataset = load_source(DataSource::reader(Cursor::new(SAMPLE.as_bytes().to_vec()))).unwrap();
+    assert_eq!(dataset.len(), 12);
+}
+
+#[test]
+fn missing_file_is_an_error() {
+    let err = load_source(DataSource::path("tests/data/does_not_exist.csv")).unwrap_err();
+    assert!(err.to_string().contains("does_not_exist.csv"));
+}
@@ -0,0 +1,130 @@
+#![cfg(feature = "sqlite")]
+
+use chrono::NaiveDate;
+use rust_stats::dataset::load_dataset;
+use rust_stats::descriptive::{calc_mean_with, MissingPolicy};
+use rust_stats::features::standardize_salary;
+use rust_stats::sqlite::{
+    load_records, read_query, read_table, write_stats, write_table, Connection, WriteMode,
+};
+use rust_stats::table::{Column, DataType, Table};
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+#[test]
+fn records_round_trip_through_sqlite() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let mut conn = Connection::open_in_memory().unwrap();
+    write_table(
+        &mut conn,
+        "salaries",
+        &Table::from_records(&dataset),
+        WriteMode::Create,
+    )
+    .unwrap();
+
+    assert_eq!(
+        load_records(&conn, "SELECT * FROM salaries").unwrap(),
+        dataset
+    );
+    let seniors = load_records(
+        &conn,
+        "SELECT * FROM salaries WHERE experience_level = 'SE' ORDER BY salary_in_usd DESC",
+    )
+    .unwrap();
+    assert!(seniors
+        .iter()
+        .all(|r| r.experience_level.to_string() == "SE"));
+}
+
+#[test]
+fn table_types_survive_a_round_trip() {
+    let table = Table::from_columns(vec![
+        ("id", Column::Int(vec![Some(1), None])),
+        ("z", Column::from(vec![Some(0.5f32), None])),
+        ("name", Column::Str(vec![None, Some("b \"quoted\"".into())])),
+        ("flag", Column::Bool(vec![Some(true), None])),
+        (
+            "day",
+            Column::Date(vec![None, NaiveDate::from_ymd_opt(2023, 1, 31)]),
+        ),
+    ])
+    .unwrap();
+    let mut conn = Connection::open_in_memory().unwrap();
+    write_table(&mut conn, "my \"table\"", &table, WriteMode::Create).unwrap();
+    assert_eq!(read_table(&conn, "my \"table\"").unwrap(), table);
+}
+
+#[test]
+fn write_modes_control_existing_tables() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    let table = Table::from_columns(vec![("n", Column::from(vec![1i64, 2]))]).unwrap();
+
+    write_table(&mut conn, "t", &table, WriteMode::Create).unwrap();
+    assert!(write_table(&mut conn, "t", &table, WriteMode::Create).is_err());
+    write_table(&mut conn, "t", &table, WriteMode::Append).unwrap();
+    assert_eq!(read_table(&conn, "t").unwrap().n_rows(), 4);
+    write_table(&mut conn, "t", &table, WriteMode::Replace).unwrap();
+    assert_eq!(read_table(&conn, "t").unwrap().n_rows(), 2);
+}
+
+#[test]
+fn untyped_columns_are_inferred_from_values() {
+    let conn = Connection::open_in_memory().unwrap();
+    let table = read_query(&conn, "SELECT 1 AS i, 1.5 AS f, 'x' AS s, NULL AS n").unwrap();
+    let dtypes: Vec<DataType> = table.columns().map(|(_, c)| c.dtype()).collect();
+    assert_eq!(
+        dtypes,
+        [DataType::Int, DataType::Float, DataType::Str, DataType::Str]
+    );
+}
+
+#[test]
+fn values_that_do_not_fit_the_declared_type_are_errors() {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch("CREATE TABLE t (flag BOOLEAN); INSERT INTO t VALUES (1), (7);")
+        .unwrap();
+    let err = read_table(&conn, "t").unwrap_err();
+    assert!(format!("{:#}", err).contains("column 'flag'"));
+    assert!(format!("{:#}", err).contains("row 2"));
+}
+
+#[test]
+fn stats_accumulate_across_runs() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let salaries: Vec<Option<f32>> = dataset.iter().map(|r| r.salary_in_usd).collect();
+    let mean = calc_mean_with(&salaries, MissingPolicy::Skip).unwrap();
+    let propagated = calc_mean_with(&[Some(1.0_f32), None], MissingPolicy::Propagate).unwrap();
+
+    let mut conn = Connection::open_in_memory().unwrap();
+    write_stats(&mut conn, "summary", "2023-01", &[("mean_usd", mean)]).unwrap();
+    write_stats(
+        &mut conn,
+        "summary",
+        "2023-02",
+        &[("mean_usd", mean), ("propagated", propagated)],
+    )
+    .unwrap();
+
+    let table = read_query(
+        &conn,
+        "SELECT run, value FROM summary WHERE statistic = 'mean_usd' ORDER BY run",
+    )
+    .unwrap();
+    assert_eq!(table.n_rows(), 2);
+    assert_eq!(
+        table.column("value").unwrap().f64_values().unwrap(),
+        [mean.value as f64; 2]
+    );
+    let nan = read_query(
+        &conn,
+        "SELECT value FROM summary WHERE statistic = 'propagated'",
+    )
+    .unwrap();
+    assert_eq!(nan.column("value").unwrap().null_count(), 1);
+
+    let z = standardize_salary(&dataset).unwrap();
+    let features = Table::from_columns(vec![("salary_z", Column::from(z))]).unwrap();
+    write_table(&mut conn, "features", &features, WriteMode::Replace).unwrap();
+    assert_eq!(read_table(&conn, "features").unwrap(), features);
+}
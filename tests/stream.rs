@@ -0,0 +1,95 @@
+use anyhow::Result;
+use rust_stats::dataset::{load_dataset, stream_records, stream_source};
+use rust_stats::descriptive::{calc_mean, calc_std, range, RunningStats};
+use rust_stats::features::{
+    create_us_based_feature, filter_and_convert, one_hot_encode_job_titles, standardize_salary,
+    stream_filter_and_convert, stream_job_title_map, stream_one_hot_encode_job_titles,
+    stream_standardize_salary, stream_us_based_feature,
+};
+use rust_stats::source::DataSource;
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+fn salary_stats() -> RunningStats {
+    stream_records(SAMPLE.as_bytes())
+        .map(|record| record.map(|record| record.salary_in_usd))
+        .collect::<Result<RunningStats>>()
+        .unwrap()
+}
+
+#[test]
+fn stream_yields_the_same_records_as_load() {
+    let streamed: Vec<_> = stream_source(DataSource::path("tests/data/ds_salaries_sample.csv"))
+        .unwrap()
+        .collect::<Result<_>>()
+        .unwrap();
+    assert_eq!(streamed, load_dataset(SAMPLE).unwrap());
+}
+
+#[test]
+fn stream_surfaces_parse_errors_per_row() {
+    let csv = "work_year,experience_level,employment_type,job_title,salary,salary_currency,salary_in_usd,employee_residence,remote_ratio,company_location,company_size\n\
+               2023,SE,FT,Data Scientist,1,USD,1,US,0,US,M\n\
+               oops,SE,FT,Data Scientist,1,USD,1,US,0,US,M\n\
+               2022,SE,FT,Data Scientist,1,USD,1,US,0,US,M\n";
+    let results: Vec<_> = stream_records(csv.as_bytes()).collect();
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+    assert!(results[2].is_ok());
+}
+
+#[test]
+fn running_stats_match_the_slice_functions() {
+    let salaries: Vec<f32> = load_dataset(SAMPLE)
+        .unwrap()
+        .iter()
+        .filter_map(|record| record.salary_in_usd)
+        .collect();
+    let stats = salary_stats();
+    assert_eq!(stats.count(), 12);
+    assert!((stats.mean().unwrap() - calc_mean(&salaries).unwrap()).abs() < 1e-2);
+    assert!((stats.std().unwrap() - calc_std(&salaries).unwrap()).abs() < 1e-2);
+    assert_eq!(stats.range().unwrap(), range(&salaries).unwrap());
+}
+
+#[test]
+fn empty_running_stats_are_errors() {
+    let stats = RunningStats::new();
+    assert!(stats.mean().is_err());
+    assert!(stats.std().is_err());
+    assert!(stats.range().is_err());
+}
+
+#[test]
+fn streaming_features_match_the_slice_features() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let records = || stream_records(SAMPLE.as_bytes());
+
+    let filtered: Vec<_> = stream_filter_and_convert(records())
+        .collect::<Result<_>>()
+        .unwrap();
+    assert_eq!(filtered, filter_and_convert(&dataset).unwrap());
+
+    let us: Vec<_> = stream_us_based_feature(records())
+        .collect::<Result<_>>()
+        .unwrap();
+    assert_eq!(us, create_us_based_feature(&dataset).unwrap());
+
+    let mapping = stream_job_title_map(records()).unwrap();
+    let encoded: Vec<_> = stream_one_hot_encode_job_titles(records(), &mapping)
+        .collect::<Result<_>>()
+        .unwrap();
+    assert_eq!(
+        encoded,
+        one_hot_encode_job_titles(&dataset, &mapping).unwrap()
+    );
+
+    let z: Vec<Option<f32>> = stream_standardize_salary(records(), &salary_stats())
+        .unwrap()
+        .collect::<Result<_>>()
+        .unwrap();
+    for (streamed, sliced) in z.iter().zip(standardize_salary(&dataset).unwrap()) {
+        assert!((streamed.unwrap() - sliced.unwrap()).abs() < 1e-4);
+    }
+}
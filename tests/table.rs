@@ -0,0 +1,155 @@
+use rust_stats::dataset::load_dataset;
+use rust_stats::features::{
+    create_us_based_feature, equals_mask, filter_and_convert, indicator, one_hot_encode,
+    standardize_column, standardize_salary,
+};
+use rust_stats::source::DataSource;
+use rust_stats::table::{Column, DataType, Table, Value};
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+#[test]
+fn csv_columns_get_typed_from_the_header() {
+    let table = Table::from_source(DataSource::path("tests/data/ds_salaries_sample.csv")).unwrap();
+    assert_eq!(table.n_rows(), 12);
+    assert_eq!(table.n_cols(), 11);
+    assert_eq!(table.column("work_year").unwrap().dtype(), DataType::Int);
+    assert_eq!(table.column("job_title").unwrap().dtype(), DataType::Str);
+    assert_eq!(
+        table.column("salary_in_usd").unwrap().dtype(),
+        DataType::Int
+    );
+    assert!(table.column("no_such_column").is_err());
+}
+
+#[test]
+fn detection_covers_floats_bools_and_missing_cells() {
+    let csv = "a,b,c,d\n1.5,true,x,\n,false,,7\n2,,y,8\n";
+    let table = Table::from_reader(csv.as_bytes()).unwrap();
+    assert_eq!(
+        table.column("a").unwrap(),
+        &Column::Float(vec![Some(1.5), None, Some(2.0)])
+    );
+    assert_eq!(
+        table.column("b").unwrap(),
+        &Column::Bool(vec![Some(true), Some(false), None])
+    );
+    assert_eq!(table.column("c").unwrap().null_count(), 1);
+    assert_eq!(table.column("d").unwrap().dtype(), DataType::Int);
+    assert_eq!(
+        table.row(1),
+        vec![Value::Null, Value::Bool(false), Value::Null, Value::Int(7)]
+    );
+}
+
+#[test]
+fn columns_must_be_unique_and_equally_long() {
+    let mut table = Table::new();
+    table
+        .add_column("a", Column::Int(vec![Some(1), Some(2)]))
+        .unwrap();
+    assert!(table
+        .add_column("a", Column::Int(vec![Some(1), Some(2)]))
+        .is_err());
+    assert!(table.add_column("b", Column::Int(vec![Some(1)])).is_err());
+}
+
+#[test]
+fn column_operations_replace_record_closures() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let table = Table::from_records(&dataset);
+
+    let z = standardize_column(table.column("salary_in_usd").unwrap()).unwrap();
+    for (cell, expected) in z
+        .to_f64()
+        .unwrap()
+        .iter()
+        .zip(standardize_salary(&dataset).unwrap())
+    {
+        assert!((cell.unwrap() as f32 - expected.unwrap()).abs() < 1e-4);
+    }
+
+    let us = indicator(table.column("company_location").unwrap(), "US").unwrap();
+    let expected: Vec<Option<i64>> = create_us_based_feature(&dataset)
+        .unwrap()
+        .into_iter()
+        .map(|flag| Some(flag as i64))
+        .collect();
+    assert_eq!(us, Column::Int(expected));
+
+    let mask = equals_mask(table.column("experience_level").unwrap(), "SE").unwrap();
+    let senior = table
+        .filter(&mask)
+        .unwrap()
+        .select(&["work_year", "job_title", "salary_in_usd"])
+        .unwrap();
+    assert_eq!(senior.n_rows(), filter_and_convert(&dataset).unwrap().len());
+    assert_eq!(
+        senior.column_names(),
+        ["work_year", "job_title", "salary_in_usd"]
+    );
+}
+
+#[test]
+fn one_hot_encode_makes_one_column_per_level() {
+    let table = Table::from_reader(SAMPLE.as_bytes()).unwrap();
+    let encoded = one_hot_encode(table.column("company_size").unwrap()).unwrap();
+    assert_eq!(encoded.column_names(), ["L", "M", "S"]);
+    assert_eq!(
+        encoded.row(0),
+        vec![Value::Int(1), Value::Int(0), Value::Int(0)]
+    );
+}
+
+#[test]
+fn tables_print_as_grids_and_round_trip_through_csv() {
+    let table = Table::from_columns(vec![
+        (
+            "level",
+            Column::Str(vec![Some("SE".into()), None, Some("MI".into())]),
+        ),
+        ("share", Column::Float(vec![Some(0.125), Some(1.0), None])),
+    ])
+    .unwrap();
+
+    assert_eq!(
+        format!("{:.2}", table),
+        "level  share\nSE      0.12\n        1.00\nMI          \n"
+    );
+
+    let mut csv = Vec::new();
+    table.write_csv(&mut csv).unwrap();
+    assert_eq!(
+        String::from_utf8(csv.clone()).unwrap(),
+        "level,share\nSE,0.125\n,1\nMI,\n"
+    );
+    assert_eq!(Table::from_reader(csv.as_slice()).unwrap(), table);
+}
+
+#[test]
+fn records_come_back_from_typed_columns() {
+    let records = load_dataset(SAMPLE).unwrap();
+    let table = Table::from_records(&records);
+    assert_eq!(table.to_records().unwrap(), records);
+
+    // Float years, as Parquet writers sometimes store them, and no
+    // optional columns at all.
+    let strs = |values: &[&str]| Column::Str(values.iter().map(|v| Some(v.to_string())).collect());
+    let mut minimal = Table::from_columns(vec![
+        ("work_year", Column::Float(vec![Some(2023.0), Some(2022.5)])),
+        ("experience_level", strs(&["SE", "MI"])),
+        ("employment_type", strs(&["FT", "FT"])),
+        ("job_title", strs(&["Data Scientist", "ML Engineer"])),
+    ])
+    .unwrap();
+    let err = minimal.to_records().unwrap_err();
+    assert_eq!(
+        format!("{:#}", err),
+        "row 1: 'work_year' should be a whole year, found 2022.5"
+    );
+
+    minimal = minimal.take(&[0]);
+    let record = &minimal.to_records().unwrap()[0];
+    assert_eq!(record.work_year, 2023);
+    assert_eq!(record.salary_in_usd, None);
+}
@@ -0,0 +1,85 @@
+use rust_stats::dataset::{load_dataset, SalaryRecord};
+use rust_stats::validation::{is_country_code, Rule, ValidationMode, ValidationReport, Validator};
+
+const SAMPLE: &str = include_str!("data/ds_salaries_sample.csv");
+
+fn broken_dataset() -> Vec<SalaryRecord> {
+    let mut dataset = load_dataset(SAMPLE).unwrap();
+    dataset[1].salary_in_usd = Some(-5.0);
+    dataset[2].remote_ratio = Some(30.0);
+    dataset[3].work_year = 1999;
+    dataset[4].company_location = Some("XX".into());
+    dataset[5].employee_residence = Some("us".into());
+    dataset[6].salary_in_usd = None;
+    dataset
+}
+
+#[test]
+fn sample_passes_the_standard_rules() {
+    let dataset = load_dataset(SAMPLE).unwrap();
+    let report = Validator::standard()
+        .mode(ValidationMode::Strict)
+        .validate(&dataset)
+        .unwrap();
+    assert!(report.is_valid());
+    assert_eq!(report.checked, dataset.len());
+    assert_eq!(report.rules.len(), 4);
+}
+
+#[test]
+fn violations_are_reported_per_rule() {
+    let report = Validator::standard().validate(&broken_dataset()).unwrap();
+    let by_rule = report.by_rule();
+
+    assert_eq!(by_rule["positive_salary_in_usd"].len(), 1);
+    assert_eq!(by_rule["positive_salary_in_usd"][0].index, 1);
+    assert_eq!(by_rule["remote_ratio"][0].index, 2);
+    assert_eq!(by_rule["work_year"][0].index, 3);
+    let countries: Vec<usize> = by_rule["country_codes"].iter().map(|v| v.index).collect();
+    assert_eq!(countries, [4, 5]);
+    assert!(by_rule["country_codes"][0].message.contains("'XX'"));
+    assert_eq!(report.violations.len(), 5);
+
+    let summary = report.to_string();
+    assert!(summary.starts_with("5 violations in 12 records"));
+    assert!(summary.contains("country_codes: 2 (first at record 4"));
+}
+
+#[test]
+fn strict_mode_refuses_violations() {
+    let err = Validator::standard()
+        .mode(ValidationMode::Strict)
+        .validate(&broken_dataset())
+        .unwrap_err();
+    let report = err.downcast_ref::<ValidationReport>().unwrap();
+    assert_eq!(report.violations.len(), 5);
+}
+
+#[test]
+fn custom_closures_run_alongside_built_ins() {
+    let validator = Validator::new()
+        .rule(Rule::positive_salary_in_usd())
+        .rule(Rule::new("has_currency", |record| {
+            match record.salary_currency {
+                Some(_) => Ok(()),
+                None => Err("salary_currency is missing".to_string()),
+            }
+        }));
+    let mut dataset = load_dataset(SAMPLE).unwrap();
+    dataset[0].salary_currency = None;
+
+    let report = validator.validate(&dataset).unwrap();
+    assert_eq!(report.rules, ["positive_salary_in_usd", "has_currency"]);
+    assert_eq!(report.by_rule()["has_currency"][0].index, 0);
+    assert!(report.by_rule()["positive_salary_in_usd"].is_empty());
+}
+
+#[test]
+fn country_codes_are_checked_against_iso_3166() {
+    assert!(is_country_code("US"));
+    assert!(is_country_code("AX"));
+    assert!(is_country_code("ZW"));
+    assert!(!is_country_code("UK"));
+    assert!(!is_country_code("us"));
+    assert!(!is_country_code("USA"));
+}